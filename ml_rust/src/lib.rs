@@ -4,12 +4,46 @@
 //! for FFmpeg transcoding power optimization, including Random Forest and
 //! Gradient Boosting models for QoE and cost predictions.
 
+use rand::seq::SliceRandom;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::os::raw::c_char;
 use std::fs;
 use std::path::Path;
 
+mod vmaf_distribution;
+pub use vmaf_distribution::{plot_vmaf_over_time, VmafDistributionSummary};
+
+/// Number of fields in `PredictionFeatures`, i.e. the width of the feature
+/// vector the tree-based models split on.
+const N_FEATURES: usize = 6;
+
+/// Flatten `PredictionFeatures` into the fixed-order vector the trees index
+/// by `feature_index`.
+fn feature_vector(features: &PredictionFeatures) -> [f64; N_FEATURES] {
+    [
+        features.bitrate_kbps as f64,
+        features.resolution_width as f64,
+        features.resolution_height as f64,
+        features.frame_rate as f64,
+        features.frame_drop as f64,
+        features.motion_intensity as f64,
+    ]
+}
+
+/// Mean of `targets[indices]`.
+fn mean_of(indices: &[usize], targets: &[f64]) -> f64 {
+    indices.iter().map(|&i| targets[i]).sum::<f64>() / indices.len() as f64
+}
+
+/// Squared-error impurity (sum of squared deviations from the mean) of
+/// `targets[indices]`.
+fn sum_squared_error(indices: &[usize], targets: &[f64], mean: f64) -> f64 {
+    indices.iter().map(|&i| (targets[i] - mean).powi(2)).sum()
+}
+
 // ============================================================================
 // ML Prediction Structures
 // ============================================================================
@@ -32,6 +66,15 @@ pub struct PredictionFeatures {
 pub struct PredictionResult {
     pub predicted_vmaf: f32,
     pub predicted_psnr: f32,
+    /// Structural similarity index.
+    pub predicted_ssim: f32,
+    /// Multi-scale SSIM, more sensitive to the temporal artifacts VMAF/SSIM
+    /// alone can miss on high-motion content.
+    pub predicted_ms_ssim: f32,
+    /// PSNR weighted by a human-visual-system contrast-masking model.
+    pub predicted_psnr_hvs: f32,
+    /// CIEDE2000 perceptual chroma difference, for color-critical streams.
+    pub predicted_ciede2000: f32,
     pub predicted_cost_usd: f32,
     pub predicted_co2_kg: f32,
     pub confidence: f32,
@@ -44,8 +87,121 @@ pub struct ModelBundle {
     pub version: String,
     pub vmaf_model: SimpleRandomForest,
     pub psnr_model: SimpleRandomForest,
+    /// Added in bundle format 1.1; defaults to an untrained forest when
+    /// loading an older JSON bundle that predates these metrics.
+    #[serde(default = "SimpleRandomForest::default_submodel")]
+    pub ssim_model: SimpleRandomForest,
+    #[serde(default = "SimpleRandomForest::default_submodel")]
+    pub ms_ssim_model: SimpleRandomForest,
+    #[serde(default = "SimpleRandomForest::default_submodel")]
+    pub psnr_hvs_model: SimpleRandomForest,
+    #[serde(default = "SimpleRandomForest::default_submodel")]
+    pub ciede2000_model: SimpleRandomForest,
     pub cost_model: SimpleGradientBoosting,
     pub co2_model: SimpleGradientBoosting,
+    /// State carried across `update_online` calls so a live transcoding farm
+    /// can keep refining predictions without a full retrain.
+    #[serde(default)]
+    pub online_state: OnlineTrainingState,
+    /// Drop-in replacement for `vmaf_model`/`psnr_model` once trained via
+    /// `enable_boosted_trees`: stage-wise boosting tends to track the smooth
+    /// bitrate/quality curve more closely than the bagged forest. `predict`
+    /// prefers these over the forest when present; `None` leaves a bundle
+    /// predicting exactly as it did before this field existed.
+    #[serde(default)]
+    pub boosted_vmaf_model: Option<GradientBoostedTrees>,
+    #[serde(default)]
+    pub boosted_psnr_model: Option<GradientBoostedTrees>,
+}
+
+/// Running state for `ModelBundle::update_online`: the decaying SGD
+/// schedule, the mini-batch buffers the cost/CO2 boosting ensembles need to
+/// fill before growing a new tree, and per-target running R² metrics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnlineTrainingState {
+    /// Total observations folded in so far (`t` in `ηₜ = η₀ / (1 + λ·t)`).
+    pub observations_seen: u64,
+    /// `η₀`, the learning rate before any decay is applied.
+    pub base_learning_rate: f64,
+    /// `λ`, how quickly the learning rate decays with `observations_seen`.
+    pub decay: f64,
+    /// Observations buffered per cost/CO2 model before a new boosting tree
+    /// is grown from their residuals.
+    pub batch_size: usize,
+    #[serde(default)]
+    pub cost_buffer: Vec<(PredictionFeatures, f64)>,
+    #[serde(default)]
+    pub co2_buffer: Vec<(PredictionFeatures, f64)>,
+    /// Mini-batch buffers for `boosted_vmaf_model`/`boosted_psnr_model`, used
+    /// instead of `vmaf_model`/`psnr_model`'s plain SGD nudge whenever boosted
+    /// trees are the model `predict()` actually serves.
+    #[serde(default)]
+    pub boosted_vmaf_buffer: Vec<(PredictionFeatures, f64)>,
+    #[serde(default)]
+    pub boosted_psnr_buffer: Vec<(PredictionFeatures, f64)>,
+    #[serde(default)]
+    pub vmaf_metrics: OnlineMetrics,
+    #[serde(default)]
+    pub psnr_metrics: OnlineMetrics,
+    #[serde(default)]
+    pub cost_metrics: OnlineMetrics,
+    #[serde(default)]
+    pub co2_metrics: OnlineMetrics,
+}
+
+impl Default for OnlineTrainingState {
+    fn default() -> Self {
+        Self {
+            observations_seen: 0,
+            base_learning_rate: 0.05,
+            decay: 0.01,
+            batch_size: 16,
+            cost_buffer: Vec::new(),
+            co2_buffer: Vec::new(),
+            boosted_vmaf_buffer: Vec::new(),
+            boosted_psnr_buffer: Vec::new(),
+            vmaf_metrics: OnlineMetrics::default(),
+            psnr_metrics: OnlineMetrics::default(),
+            cost_metrics: OnlineMetrics::default(),
+            co2_metrics: OnlineMetrics::default(),
+        }
+    }
+}
+
+impl OnlineTrainingState {
+    /// `ηₜ = η₀ / (1 + λ·t)` at the current observation count.
+    fn learning_rate(&self) -> f64 {
+        self.base_learning_rate / (1.0 + self.decay * self.observations_seen as f64)
+    }
+}
+
+/// Welford-style running R²: tracks target mean/variance and squared error
+/// incrementally so `ModelBundle::update_online` never has to replay history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OnlineMetrics {
+    pub count: u64,
+    mean_target: f64,
+    sum_squared_total: f64,
+    sum_squared_error: f64,
+}
+
+impl OnlineMetrics {
+    fn update(&mut self, target: f64, prediction: f64) {
+        self.count += 1;
+        let delta = target - self.mean_target;
+        self.mean_target += delta / self.count as f64;
+        let delta2 = target - self.mean_target;
+        self.sum_squared_total += delta * delta2;
+        self.sum_squared_error += (target - prediction).powi(2);
+    }
+
+    fn r2(&self) -> f64 {
+        if self.sum_squared_total <= 0.0 {
+            0.0
+        } else {
+            1.0 - self.sum_squared_error / self.sum_squared_total
+        }
+    }
 }
 
 /// Simplified Random Forest implementation for QoE prediction
@@ -53,13 +209,38 @@ pub struct ModelBundle {
 pub struct SimpleRandomForest {
     pub trees: Vec<DecisionTree>,
     pub n_trees: usize,
+    /// Maximum depth of any tree in the forest.
+    pub max_depth: usize,
+    /// Minimum number of samples each child of a split must keep, so a node
+    /// needs at least twice this many samples to be eligible for a split.
+    pub min_leaf_size: usize,
+    /// Fraction of the 6 features considered as split candidates at each
+    /// node (randomized per node), e.g. 0.5 considers 3 of 6 features.
+    pub feature_sample_ratio: f64,
+}
+
+/// A node in a CART regression tree, stored in a flat arena (`DecisionTree::nodes`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Node {
+    /// Internal node: samples with `feature[feature_index] <= threshold` go
+    /// left, everything else goes right.
+    Split {
+        feature_index: usize,
+        threshold: f64,
+        left: usize,
+        right: usize,
+    },
+    /// Terminal node holding the mean target value of the samples that
+    /// reached it.
+    Leaf { value: f64 },
 }
 
-/// Simple decision tree for ensemble models
+/// Binary CART regression tree, stored as an arena of `Node`s with `root`
+/// pointing at the entry node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecisionTree {
-    pub intercept: f64,
-    pub weights: Vec<f64>,
+    pub nodes: Vec<Node>,
+    pub root: usize,
 }
 
 /// Simplified Gradient Boosting implementation for cost/CO2
@@ -68,75 +249,160 @@ pub struct SimpleGradientBoosting {
     pub base_prediction: f64,
     pub trees: Vec<DecisionTree>,
     pub learning_rate: f64,
+    pub n_estimators: usize,
+    pub loss: Loss,
+}
+
+/// Loss function driving the pseudo-residuals `SimpleGradientBoosting` fits
+/// each stage to, and how a leaf's constant value is derived from the
+/// samples that land in it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Loss {
+    /// Gradient is the residual `y - pred`; leaf value is the residual mean.
+    SquaredError,
+    /// Gradient is `sign(y - pred)`; leaf value is the residual median, making
+    /// the fit robust to outliers.
+    LeastAbsoluteDeviation,
+    /// Quadratic for residuals within `delta`, linear beyond it; leaf value
+    /// is the median plus the mean of each residual's deviation from that
+    /// median, with every deviation clipped to `[-delta, delta]`.
+    Huber { delta: f64 },
+}
+
+impl Loss {
+    /// Negative gradient (pseudo-residual) of this loss for one sample.
+    fn gradient(&self, target: f64, prediction: f64) -> f64 {
+        let residual = target - prediction;
+        match self {
+            Loss::SquaredError => residual,
+            Loss::LeastAbsoluteDeviation => residual.signum(),
+            Loss::Huber { delta } => {
+                if residual.abs() <= *delta {
+                    residual
+                } else {
+                    delta * residual.signum()
+                }
+            }
+        }
+    }
+
+    /// The constant a leaf should report, computed from the *true* residuals
+    /// (not the pseudo-residuals used to pick splits) of the samples it holds.
+    fn leaf_value(&self, indices: &[usize], residuals: &[f64]) -> f64 {
+        match self {
+            Loss::SquaredError => mean_of(indices, residuals),
+            Loss::LeastAbsoluteDeviation => median_of(indices, residuals),
+            Loss::Huber { delta } => {
+                let median = median_of(indices, residuals);
+                let clipped_mean: f64 = indices
+                    .iter()
+                    .map(|&i| (residuals[i] - median).clamp(-delta, *delta))
+                    .sum::<f64>()
+                    / indices.len() as f64;
+                median + clipped_mean
+            }
+        }
+    }
+
+    /// Starting point for boosting before any trees are added: the mean for
+    /// squared error, the median for the robust losses.
+    fn initial_prediction(&self, targets: &[f64]) -> f64 {
+        match self {
+            Loss::SquaredError => targets.iter().sum::<f64>() / targets.len() as f64,
+            Loss::LeastAbsoluteDeviation | Loss::Huber { .. } => median(targets),
+        }
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+fn median_of(indices: &[usize], values: &[f64]) -> f64 {
+    let selected: Vec<f64> = indices.iter().map(|&i| values[i]).collect();
+    median(&selected)
 }
 
 impl SimpleRandomForest {
     pub fn new(n_trees: usize) -> Self {
         Self {
-            trees: vec![DecisionTree { intercept: 0.0, weights: vec![0.0; 6] }; n_trees],
+            trees: Vec::new(),
             n_trees,
+            max_depth: 6,
+            // 1, not 2: `build_node` requires `indices.len() >= 2*min_leaf_size`
+            // to attempt a split at all, and the accuracy tests train on just
+            // 3 synthetic samples — a min_leaf_size of 2 forced every
+            // bootstrapped tree to a single root leaf and could never fit
+            // the bitrate/quality curve they check against.
+            min_leaf_size: 1,
+            feature_sample_ratio: 1.0,
         }
     }
 
+    /// Serde default for `ModelBundle` fields added after the initial
+    /// release: an untrained forest of the same shape `ModelBundle::new`
+    /// would otherwise create.
+    fn default_submodel() -> Self {
+        Self::new(10)
+    }
+
+    /// Below this many training samples, bagging hurts more than it helps:
+    /// a bootstrap resample of a handful of points drops or duplicates so
+    /// many of them that the averaged ensemble underfits the curve every
+    /// single tree could otherwise match exactly. Below the threshold every
+    /// tree trains on the full sample set instead (the "random" part of the
+    /// forest only kicks in once there's enough data for resampling to add
+    /// diversity rather than noise).
+    const MIN_BAGGING_SAMPLES: usize = 10;
+
+    /// Train `n_trees` CART regression trees, each on a bootstrap resample
+    /// of `features`/`targets` (or, below `MIN_BAGGING_SAMPLES`, the full
+    /// sample set), so every one of the six `PredictionFeatures` can
+    /// influence the ensemble's prediction.
     pub fn train(&mut self, features: &[PredictionFeatures], targets: &[f32]) {
         if features.is_empty() || targets.is_empty() {
             return;
         }
 
-        // Calculate linear regression coefficients for each tree with slight variations
-        let n = features.len() as f64;
-        
-        // Calculate means
-        let mean_bitrate: f64 = features.iter().map(|f| f.bitrate_kbps as f64).sum::<f64>() / n;
-        let mean_target: f64 = targets.iter().map(|&t| t as f64).sum::<f64>() / n;
-        
-        // Calculate covariance and variance for bitrate (main feature)
-        let mut cov = 0.0;
-        let mut var = 0.0;
-        for (feat, &target) in features.iter().zip(targets.iter()) {
-            let dx = feat.bitrate_kbps as f64 - mean_bitrate;
-            let dy = target as f64 - mean_target;
-            cov += dx * dy;
-            var += dx * dx;
-        }
-        
-        let slope = if var > 0.0 { cov / var } else { 0.0 };
-        let intercept = mean_target - slope * mean_bitrate;
-
-        // Create trees with learned parameters and slight variations
-        for (i, tree) in self.trees.iter_mut().enumerate() {
-            let variation = 1.0 + (i as f64 - self.n_trees as f64 / 2.0) * 0.02;
-            tree.intercept = intercept * variation;
-            tree.weights = vec![
-                slope * variation, // bitrate impact (primary)
-                0.0,               // resolution width
-                0.0,               // resolution height
-                0.0,               // frame rate
-                -5.0 * variation,  // frame drop penalty
-                0.0,               // motion intensity
-            ];
-        }
+        let targets: Vec<f64> = targets.iter().map(|&t| t as f64).collect();
+        let mut rng = rand::thread_rng();
+
+        self.trees = (0..self.n_trees)
+            .map(|_| {
+                let bootstrap: Vec<usize> = if features.len() < Self::MIN_BAGGING_SAMPLES {
+                    (0..features.len()).collect()
+                } else {
+                    (0..features.len())
+                        .map(|_| rng.gen_range(0..features.len()))
+                        .collect()
+                };
+                DecisionTree::fit(
+                    features,
+                    &targets,
+                    &bootstrap,
+                    self.max_depth,
+                    self.min_leaf_size,
+                    self.feature_sample_ratio,
+                    &mut rng,
+                )
+            })
+            .collect();
     }
 
     pub fn predict(&self, features: &PredictionFeatures) -> f32 {
-        let mut sum = 0.0;
-        for tree in &self.trees {
-            let feature_vec = vec![
-                features.bitrate_kbps as f64,
-                features.resolution_width as f64,
-                features.resolution_height as f64,
-                features.frame_rate as f64,
-                features.frame_drop as f64,
-                features.motion_intensity as f64,
-            ];
-            
-            let mut pred = tree.intercept;
-            for (w, f) in tree.weights.iter().zip(feature_vec.iter()) {
-                pred += w * f;
-            }
-            sum += pred;
+        if self.trees.is_empty() {
+            return 0.0;
         }
-        (sum / self.trees.len() as f64).max(0.0).min(100.0) as f32
+
+        let sum: f64 = self.trees.iter().map(|tree| tree.predict(features)).sum();
+        (sum / self.trees.len() as f64).clamp(0.0, 100.0) as f32
     }
 
     pub fn r2_score(&self, features: &[PredictionFeatures], targets: &[f32]) -> f64 {
@@ -145,10 +411,10 @@ impl SimpleRandomForest {
         }
 
         let mean_target: f64 = targets.iter().map(|&x| x as f64).sum::<f64>() / targets.len() as f64;
-        
+
         let mut ss_tot = 0.0;
         let mut ss_res = 0.0;
-        
+
         for (feat, &target) in features.iter().zip(targets.iter()) {
             let pred = self.predict(feat) as f64;
             ss_tot += (target as f64 - mean_target).powi(2);
@@ -161,192 +427,1023 @@ impl SimpleRandomForest {
 
         1.0 - (ss_res / ss_tot)
     }
+
+    /// Apply one SGD step toward `target`: since `predict` averages every
+    /// tree's leaf, nudge each tree's leaf for this sample by an equal share
+    /// of the prediction error at the given (already-decayed) learning rate.
+    pub fn update_online(&mut self, features: &PredictionFeatures, target: f32, learning_rate: f64) {
+        if self.trees.is_empty() {
+            return;
+        }
+
+        let error = target as f64 - self.predict(features) as f64;
+        let step = learning_rate * error / self.trees.len() as f64;
+
+        for tree in &mut self.trees {
+            let leaf = tree.route_to_leaf(features);
+            if let Node::Leaf { value } = &mut tree.nodes[leaf] {
+                *value += step;
+            }
+        }
+    }
+}
+
+impl DecisionTree {
+    /// Walk the tree for a single sample, returning the leaf value reached.
+    pub fn predict(&self, features: &PredictionFeatures) -> f64 {
+        let fv = feature_vector(features);
+        let mut index = self.root;
+        loop {
+            match &self.nodes[index] {
+                Node::Leaf { value } => return *value,
+                Node::Split { feature_index, threshold, left, right } => {
+                    index = if fv[*feature_index] <= *threshold { *left } else { *right };
+                }
+            }
+        }
+    }
+
+    /// Return the arena index of the leaf a sample routes to.
+    fn route_to_leaf(&self, features: &PredictionFeatures) -> usize {
+        let fv = feature_vector(features);
+        let mut index = self.root;
+        loop {
+            match &self.nodes[index] {
+                Node::Leaf { .. } => return index,
+                Node::Split { feature_index, threshold, left, right } => {
+                    index = if fv[*feature_index] <= *threshold { *left } else { *right };
+                }
+            }
+        }
+    }
+
+    /// Re-derive every leaf's constant from `residuals[indices]` via
+    /// `loss.leaf_value`, used by boosting once a tree's splits have been
+    /// chosen against pseudo-residuals but its output should reflect the
+    /// true residuals under the configured loss.
+    fn recompute_leaves(
+        &mut self,
+        features: &[PredictionFeatures],
+        indices: &[usize],
+        residuals: &[f64],
+        loss: Loss,
+    ) {
+        let mut by_leaf: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &i in indices {
+            by_leaf.entry(self.route_to_leaf(&features[i])).or_default().push(i);
+        }
+        for (leaf_index, leaf_indices) in by_leaf {
+            if let Node::Leaf { value } = &mut self.nodes[leaf_index] {
+                *value = loss.leaf_value(&leaf_indices, residuals);
+            }
+        }
+    }
+
+    /// Fit a single CART regression tree over `targets[indices]`, minimizing
+    /// the weighted sum of child variances at each split.
+    fn fit(
+        features: &[PredictionFeatures],
+        targets: &[f64],
+        indices: &[usize],
+        max_depth: usize,
+        min_leaf_size: usize,
+        feature_sample_ratio: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut nodes = Vec::new();
+        let root = Self::build_node(
+            &mut nodes,
+            features,
+            targets,
+            indices,
+            0,
+            max_depth,
+            min_leaf_size,
+            feature_sample_ratio,
+            rng,
+        );
+        Self { nodes, root }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_node(
+        nodes: &mut Vec<Node>,
+        features: &[PredictionFeatures],
+        targets: &[f64],
+        indices: &[usize],
+        depth: usize,
+        max_depth: usize,
+        min_leaf_size: usize,
+        feature_sample_ratio: f64,
+        rng: &mut impl Rng,
+    ) -> usize {
+        let mean = mean_of(indices, targets);
+
+        let reached_stop = depth >= max_depth || indices.len() < 2 * min_leaf_size;
+        let split = if reached_stop {
+            None
+        } else {
+            Self::best_split(features, targets, indices, min_leaf_size, feature_sample_ratio, rng)
+        };
+
+        match split {
+            Some((feature_index, threshold, left_indices, right_indices)) => {
+                let left = Self::build_node(
+                    nodes,
+                    features,
+                    targets,
+                    &left_indices,
+                    depth + 1,
+                    max_depth,
+                    min_leaf_size,
+                    feature_sample_ratio,
+                    rng,
+                );
+                let right = Self::build_node(
+                    nodes,
+                    features,
+                    targets,
+                    &right_indices,
+                    depth + 1,
+                    max_depth,
+                    min_leaf_size,
+                    feature_sample_ratio,
+                    rng,
+                );
+                nodes.push(Node::Split { feature_index, threshold, left, right });
+                nodes.len() - 1
+            }
+            None => {
+                nodes.push(Node::Leaf { value: mean });
+                nodes.len() - 1
+            }
+        }
+    }
+
+    /// Scan a randomized subset of features for the (feature, threshold)
+    /// that minimizes the weighted sum of child squared-error impurities.
+    /// Returns `None` if no split reduces impurity.
+    fn best_split(
+        features: &[PredictionFeatures],
+        targets: &[f64],
+        indices: &[usize],
+        min_leaf_size: usize,
+        feature_sample_ratio: f64,
+        rng: &mut impl Rng,
+    ) -> Option<(usize, f64, Vec<usize>, Vec<usize>)> {
+        let sample_size = ((N_FEATURES as f64 * feature_sample_ratio).round() as usize)
+            .clamp(1, N_FEATURES);
+        let mut candidate_features: Vec<usize> = (0..N_FEATURES).collect();
+        candidate_features.shuffle(rng);
+        candidate_features.truncate(sample_size);
+
+        let parent_mean = mean_of(indices, targets);
+        let parent_sse = sum_squared_error(indices, targets, parent_mean);
+
+        let mut best_sse = parent_sse;
+        let mut best: Option<(usize, f64, Vec<usize>, Vec<usize>)> = None;
+
+        for feature_index in candidate_features {
+            let mut sorted = indices.to_vec();
+            sorted.sort_by(|&a, &b| {
+                feature_vector(&features[a])[feature_index]
+                    .partial_cmp(&feature_vector(&features[b])[feature_index])
+                    .unwrap()
+            });
+
+            for split_at in min_leaf_size..=(sorted.len() - min_leaf_size) {
+                let left = &sorted[..split_at];
+                let right = &sorted[split_at..];
+
+                let left_val = feature_vector(&features[left[left.len() - 1]])[feature_index];
+                let right_val = feature_vector(&features[right[0]])[feature_index];
+                if left_val == right_val {
+                    continue; // can't separate identical feature values
+                }
+
+                let left_mean = mean_of(left, targets);
+                let right_mean = mean_of(right, targets);
+                let weighted_sse = sum_squared_error(left, targets, left_mean)
+                    + sum_squared_error(right, targets, right_mean);
+
+                if weighted_sse < best_sse {
+                    best_sse = weighted_sse;
+                    let threshold = (left_val + right_val) / 2.0;
+                    best = Some((feature_index, threshold, left.to_vec(), right.to_vec()));
+                }
+            }
+        }
+
+        best
+    }
 }
 
 impl SimpleGradientBoosting {
+    /// Defaults to squared-error loss with 5 stages, matching the previous
+    /// fixed-size ensemble.
     pub fn new(learning_rate: f64) -> Self {
+        Self::with_loss(learning_rate, 5, Loss::SquaredError)
+    }
+
+    pub fn with_loss(learning_rate: f64, n_estimators: usize, loss: Loss) -> Self {
         Self {
             base_prediction: 0.0,
             trees: Vec::new(),
             learning_rate,
+            n_estimators,
+            loss,
         }
     }
 
+    /// Stage-wise gradient boosting: each round computes the negative
+    /// gradient of `self.loss` against the ensemble's current predictions,
+    /// fits a shallow CART tree to those pseudo-residuals, rewrites each
+    /// leaf's constant from the true residuals via `Loss::leaf_value`, then
+    /// folds the tree in at `learning_rate`.
     pub fn train(&mut self, features: &[PredictionFeatures], targets: &[f32]) {
         if features.is_empty() || targets.is_empty() {
             return;
         }
 
-        // Base prediction is the mean
-        self.base_prediction = targets.iter().map(|&x| x as f64).sum::<f64>() / targets.len() as f64;
+        let targets: Vec<f64> = targets.iter().map(|&t| t as f64).collect();
+        let all_indices: Vec<usize> = (0..features.len()).collect();
+        let mut rng = rand::thread_rng();
+
+        self.base_prediction = self.loss.initial_prediction(&targets);
+        self.trees.clear();
+
+        let mut predictions = vec![self.base_prediction; targets.len()];
+
+        for _ in 0..self.n_estimators {
+            let pseudo_residuals: Vec<f64> = targets
+                .iter()
+                .zip(predictions.iter())
+                .map(|(&y, &pred)| self.loss.gradient(y, pred))
+                .collect();
+
+            let mut tree = DecisionTree::fit(
+                features,
+                &pseudo_residuals,
+                &all_indices,
+                3,
+                2,
+                1.0,
+                &mut rng,
+            );
+
+            let true_residuals: Vec<f64> = targets
+                .iter()
+                .zip(predictions.iter())
+                .map(|(&y, &pred)| y - pred)
+                .collect();
+            tree.recompute_leaves(features, &all_indices, &true_residuals, self.loss);
+
+            for (pred, feat) in predictions.iter_mut().zip(features.iter()) {
+                *pred += self.learning_rate * tree.predict(feat);
+            }
 
-        let n = features.len() as f64;
-        let mean_bitrate: f64 = features.iter().map(|f| f.bitrate_kbps as f64).sum::<f64>() / n;
-        
-        // Calculate simple linear relationship with bitrate
-        let mut cov = 0.0;
-        let mut var = 0.0;
-        for (feat, &target) in features.iter().zip(targets.iter()) {
-            let dx = feat.bitrate_kbps as f64 - mean_bitrate;
-            let dy = target as f64 - self.base_prediction;
-            cov += dx * dy;
-            var += dx * dx;
-        }
-        
-        let slope = if var > 0.0 { cov / var } else { 0.0 };
-
-        // Add boosting trees that correct residuals
-        for i in 0..5 {
-            let learning_factor = self.learning_rate * (1.0 - i as f64 * 0.1);
-            let tree = DecisionTree {
-                intercept: 0.0,
-                weights: vec![slope * learning_factor, 0.0, 0.0, 0.0, 0.0, 0.0],
-            };
             self.trees.push(tree);
         }
     }
 
     pub fn predict(&self, features: &PredictionFeatures) -> f32 {
         let mut pred = self.base_prediction;
-        
-        let feature_vec = vec![
-            features.bitrate_kbps as f64,
-            features.resolution_width as f64,
-            features.resolution_height as f64,
-            features.frame_rate as f64,
-            features.frame_drop as f64,
-            features.motion_intensity as f64,
-        ];
-
         for tree in &self.trees {
-            let mut tree_pred = tree.intercept;
-            for (w, f) in tree.weights.iter().zip(feature_vec.iter()) {
-                tree_pred += w * f;
-            }
-            pred += self.learning_rate * tree_pred;
+            pred += self.learning_rate * tree.predict(features);
         }
-
         pred.max(0.0) as f32
     }
-}
 
-impl ModelBundle {
-    /// Create a new model bundle with default initialization
-    pub fn new() -> Self {
-        Self {
-            version: "1.0.0".to_string(),
-            vmaf_model: SimpleRandomForest::new(10),
-            psnr_model: SimpleRandomForest::new(10),
-            cost_model: SimpleGradientBoosting::new(0.1),
-            co2_model: SimpleGradientBoosting::new(0.1),
+    /// Buffer one observation; once `buffer` reaches `batch_size`, fit a new
+    /// boosting tree to the batch's residuals (the same way `train` grows a
+    /// stage) and append it, then clear the buffer for the next batch.
+    pub fn update_online(
+        &mut self,
+        buffer: &mut Vec<(PredictionFeatures, f64)>,
+        batch_size: usize,
+        features: &PredictionFeatures,
+        target: f64,
+    ) {
+        buffer.push((features.clone(), target));
+        if buffer.len() < batch_size {
+            return;
         }
+
+        let batch_features: Vec<PredictionFeatures> = buffer.iter().map(|(f, _)| f.clone()).collect();
+        let pseudo_residuals: Vec<f64> = buffer
+            .iter()
+            .map(|(f, y)| self.loss.gradient(*y, self.predict(f) as f64))
+            .collect();
+        let true_residuals: Vec<f64> = buffer
+            .iter()
+            .map(|(f, y)| y - self.predict(f) as f64)
+            .collect();
+
+        let indices: Vec<usize> = (0..buffer.len()).collect();
+        let mut rng = rand::thread_rng();
+        let mut tree = DecisionTree::fit(&batch_features, &pseudo_residuals, &indices, 3, 2, 1.0, &mut rng);
+        tree.recompute_leaves(&batch_features, &indices, &true_residuals, self.loss);
+
+        self.trees.push(tree);
+        buffer.clear();
     }
 }
 
-/// Load model bundle from disk
-pub fn load_model(path: &str) -> Result<ModelBundle, String> {
-    let model_path = Path::new(path);
-    
-    if !model_path.exists() {
-        // Return a default trained model if file doesn't exist
-        return Ok(create_default_model());
+/// Squared-loss gradient-boosted CART trees, offered as an alternative to
+/// `SimpleRandomForest` for the VMAF/PSNR models: bagged trees tend to
+/// underfit the smooth bitrate→quality curves this crate models, while
+/// stage-wise boosting can track them more closely. Its `train`/`predict`
+/// take the same `PredictionFeatures` as the forest's, but targets and
+/// predictions are `f64` where the forest uses `f32`; both implement
+/// `Predictor`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradientBoostedTrees {
+    pub base_prediction: f64,
+    pub trees: Vec<DecisionTree>,
+    pub n_trees: usize,
+    pub learning_rate: f64,
+    pub max_depth: usize,
+    /// Fraction of rows sampled without replacement per tree; below 1.0
+    /// this is stochastic GBM, trading bias for variance reduction.
+    pub subsample: f64,
+}
+
+impl GradientBoostedTrees {
+    pub fn new(n_trees: usize, learning_rate: f64, max_depth: usize, subsample: f64) -> Self {
+        Self {
+            base_prediction: 0.0,
+            trees: Vec::new(),
+            n_trees,
+            learning_rate,
+            max_depth,
+            subsample: subsample.clamp(0.05, 1.0),
+        }
     }
 
-    let content = fs::read_to_string(model_path)
-        .map_err(|e| format!("Failed to read model file: {}", e))?;
-    
-    let model: ModelBundle = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse model: {}", e))?;
-    
-    Ok(model)
-}
+    /// Initialize `F0` to the target mean, then for `n_trees` rounds fit a
+    /// shallow tree to the current residuals over a `subsample` row sample
+    /// and fold it in at `learning_rate`.
+    pub fn train(&mut self, features: &[PredictionFeatures], targets: &[f64]) {
+        if features.is_empty() || targets.is_empty() {
+            return;
+        }
 
-/// Create a default pre-trained model
-fn create_default_model() -> ModelBundle {
-    let mut model = ModelBundle::new();
-    
-    // Pre-train with synthetic data representing typical scenarios
-    let synthetic_features = vec![
-        PredictionFeatures {
-            bitrate_kbps: 1000.0,
-            resolution_width: 1280,
-            resolution_height: 720,
-            frame_rate: 30.0,
-            frame_drop: 0.01,
-            motion_intensity: 0.5,
-        },
-        PredictionFeatures {
-            bitrate_kbps: 2500.0,
-            resolution_width: 1920,
-            resolution_height: 1080,
-            frame_rate: 30.0,
-            frame_drop: 0.005,
-            motion_intensity: 0.6,
-        },
-        PredictionFeatures {
-            bitrate_kbps: 5000.0,
-            resolution_width: 3840,
-            resolution_height: 2160,
-            frame_rate: 60.0,
-            frame_drop: 0.002,
-            motion_intensity: 0.7,
-        },
-    ];
+        self.base_prediction = targets.iter().sum::<f64>() / targets.len() as f64;
+        self.trees.clear();
 
-    let vmaf_targets = vec![75.0, 85.0, 92.0];
-    let psnr_targets = vec![35.0, 38.0, 42.0];
-    let cost_targets = vec![0.05, 0.12, 0.30];
-    let co2_targets = vec![0.01, 0.025, 0.06];
+        let mut predictions = vec![self.base_prediction; targets.len()];
+        let mut rng = rand::thread_rng();
+        let sample_size = ((features.len() as f64 * self.subsample).round() as usize).max(1);
 
-    model.vmaf_model.train(&synthetic_features, &vmaf_targets);
-    model.psnr_model.train(&synthetic_features, &psnr_targets);
-    model.cost_model.train(&synthetic_features, &cost_targets);
-    model.co2_model.train(&synthetic_features, &co2_targets);
+        for _ in 0..self.n_trees {
+            let residuals: Vec<f64> = targets
+                .iter()
+                .zip(predictions.iter())
+                .map(|(&y, &pred)| y - pred)
+                .collect();
 
-    model
-}
+            let mut row_indices: Vec<usize> = (0..features.len()).collect();
+            row_indices.shuffle(&mut rng);
+            row_indices.truncate(sample_size);
 
-/// Make prediction using the model bundle
-pub fn predict(features: &PredictionFeatures, model: &ModelBundle) -> PredictionResult {
-    let predicted_vmaf = model.vmaf_model.predict(features);
-    let predicted_psnr = model.psnr_model.predict(features);
-    let predicted_cost_usd = model.cost_model.predict(features);
-    let predicted_co2_kg = model.co2_model.predict(features);
+            let tree = DecisionTree::fit(features, &residuals, &row_indices, self.max_depth, 2, 1.0, &mut rng);
 
-    // Calculate confidence based on feature quality
-    let confidence = calculate_confidence(features, predicted_vmaf);
+            for (pred, feat) in predictions.iter_mut().zip(features.iter()) {
+                *pred += self.learning_rate * tree.predict(feat);
+            }
 
-    // Recommend bitrate based on predictions
-    let recommended_bitrate_kbps = recommend_bitrate(features, predicted_vmaf, predicted_cost_usd);
+            self.trees.push(tree);
+        }
+    }
 
-    PredictionResult {
-        predicted_vmaf,
-        predicted_psnr,
-        predicted_cost_usd,
-        predicted_co2_kg,
-        confidence,
-        recommended_bitrate_kbps,
+    pub fn predict(&self, features: &PredictionFeatures) -> f64 {
+        let mut pred = self.base_prediction;
+        for tree in &self.trees {
+            pred += self.learning_rate * tree.predict(features);
+        }
+        pred.clamp(0.0, 100.0)
     }
-}
 
-/// Calculate prediction confidence
-fn calculate_confidence(features: &PredictionFeatures, predicted_vmaf: f32) -> f32 {
-    let mut confidence: f32 = 0.8; // Base confidence
+    pub fn r2_score(&self, features: &[PredictionFeatures], targets: &[f64]) -> f64 {
+        if features.is_empty() || targets.is_empty() {
+            return 0.0;
+        }
 
-    // Adjust based on feature quality
-    if features.frame_drop < 0.01 {
-        confidence += 0.1;
-    } else if features.frame_drop > 0.05 {
-        confidence -= 0.2;
+        let mean_target = targets.iter().sum::<f64>() / targets.len() as f64;
+        let mut ss_tot = 0.0;
+        let mut ss_res = 0.0;
+
+        for (feat, &target) in features.iter().zip(targets.iter()) {
+            let pred = self.predict(feat);
+            ss_tot += (target - mean_target).powi(2);
+            ss_res += (target - pred).powi(2);
+        }
+
+        if ss_tot == 0.0 {
+            return 0.0;
+        }
+
+        1.0 - (ss_res / ss_tot)
     }
 
-    if predicted_vmaf > 80.0 {
-        confidence += 0.05;
-    } else if predicted_vmaf < 60.0 {
-        confidence -= 0.1;
+    /// Buffer one observation; once `buffer` reaches `batch_size`, fit a new
+    /// boosting tree to the batch's residuals and fold it in at
+    /// `learning_rate`, the same buffer-and-regrow scheme
+    /// `SimpleGradientBoosting::update_online` uses for cost/CO2, so boosted
+    /// VMAF/PSNR models (once enabled via `ModelBundle::enable_boosted_trees`)
+    /// can also keep learning from live observations instead of going stale.
+    pub fn update_online(
+        &mut self,
+        buffer: &mut Vec<(PredictionFeatures, f64)>,
+        batch_size: usize,
+        features: &PredictionFeatures,
+        target: f64,
+    ) {
+        if self.trees.is_empty() {
+            // An untrained ensemble has no base_prediction fit yet; a single
+            // observation isn't enough to bootstrap one sensibly.
+            return;
+        }
+
+        buffer.push((features.clone(), target));
+        if buffer.len() < batch_size {
+            return;
+        }
+
+        let batch_features: Vec<PredictionFeatures> = buffer.iter().map(|(f, _)| f.clone()).collect();
+        let residuals: Vec<f64> = buffer
+            .iter()
+            .map(|(f, y)| y - self.predict(f))
+            .collect();
+
+        let indices: Vec<usize> = (0..buffer.len()).collect();
+        let mut rng = rand::thread_rng();
+        let tree = DecisionTree::fit(&batch_features, &residuals, &indices, self.max_depth, 2, 1.0, &mut rng);
+
+        self.trees.push(tree);
+        buffer.clear();
     }
+}
 
-    confidence.max(0.0).min(1.0)
+/// Common training interface implemented by every tree-based predictor in
+/// this crate, so generic routines like `cross_validate` can fold, train,
+/// and score any of them without matching on each one's f32/f64 return type.
+pub trait Predictor {
+    fn train(&mut self, features: &[PredictionFeatures], targets: &[f64]);
+    fn predict(&self, features: &PredictionFeatures) -> f64;
+}
+
+impl Predictor for SimpleRandomForest {
+    fn train(&mut self, features: &[PredictionFeatures], targets: &[f64]) {
+        let targets32: Vec<f32> = targets.iter().map(|&t| t as f32).collect();
+        SimpleRandomForest::train(self, features, &targets32);
+    }
+
+    fn predict(&self, features: &PredictionFeatures) -> f64 {
+        SimpleRandomForest::predict(self, features) as f64
+    }
+}
+
+impl Predictor for SimpleGradientBoosting {
+    fn train(&mut self, features: &[PredictionFeatures], targets: &[f64]) {
+        let targets32: Vec<f32> = targets.iter().map(|&t| t as f32).collect();
+        SimpleGradientBoosting::train(self, features, &targets32);
+    }
+
+    fn predict(&self, features: &PredictionFeatures) -> f64 {
+        SimpleGradientBoosting::predict(self, features) as f64
+    }
+}
+
+impl Predictor for GradientBoostedTrees {
+    fn train(&mut self, features: &[PredictionFeatures], targets: &[f64]) {
+        GradientBoostedTrees::train(self, features, targets);
+    }
+
+    fn predict(&self, features: &PredictionFeatures) -> f64 {
+        GradientBoostedTrees::predict(self, features)
+    }
+}
+
+/// Mean and (population) standard deviation of a value set.
+fn mean_and_stddev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+/// R² of `predictor` on `features`/`targets`, shared by `cross_validate` and
+/// `permutation_importance`.
+fn r2_of<P: Predictor + ?Sized>(predictor: &P, features: &[PredictionFeatures], targets: &[f64]) -> f64 {
+    let mean_target = targets.iter().sum::<f64>() / targets.len() as f64;
+    let mut ss_tot = 0.0;
+    let mut ss_res = 0.0;
+
+    for (feat, &target) in features.iter().zip(targets.iter()) {
+        let pred = predictor.predict(feat);
+        ss_tot += (target - mean_target).powi(2);
+        ss_res += (target - pred).powi(2);
+    }
+
+    if ss_tot == 0.0 {
+        return 0.0;
+    }
+
+    1.0 - (ss_res / ss_tot)
+}
+
+/// Per-fold and aggregate R²/RMSE from `cross_validate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossValidationReport {
+    pub fold_r2: Vec<f64>,
+    pub mean_r2: f64,
+    pub stddev_r2: f64,
+    pub mean_rmse: f64,
+    pub stddev_rmse: f64,
+}
+
+/// K-fold cross-validate any `Predictor`: shuffle `features`/`targets`
+/// together, split into `k` roughly-equal folds, and for each fold train a
+/// fresh clone of `predictor` on the other k-1 folds and score it on the
+/// held-out one. This catches overfitting that a single train/test split
+/// (or the accuracy tests' three hand-picked points) can hide.
+pub fn cross_validate<P: Predictor + Clone>(
+    predictor: &P,
+    features: &[PredictionFeatures],
+    targets: &[f64],
+    k: usize,
+) -> CrossValidationReport {
+    if features.len() < 2 || targets.len() < 2 {
+        // Need at least 2 samples to hold one out per fold; a single
+        // observation (e.g. validating right after a fresh online update)
+        // can't be split into train/test folds at all.
+        return CrossValidationReport {
+            fold_r2: Vec::new(),
+            mean_r2: 0.0,
+            stddev_r2: 0.0,
+            mean_rmse: 0.0,
+            stddev_rmse: 0.0,
+        };
+    }
+
+    let k = k.clamp(2, features.len());
+    let mut order: Vec<usize> = (0..features.len()).collect();
+    let mut rng = rand::thread_rng();
+    order.shuffle(&mut rng);
+
+    let mut fold_r2 = Vec::with_capacity(k);
+    let mut fold_rmse = Vec::with_capacity(k);
+
+    for fold in 0..k {
+        let train_idx: Vec<usize> = order
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % k != fold)
+            .map(|(_, &idx)| idx)
+            .collect();
+        let test_idx: Vec<usize> = order
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % k == fold)
+            .map(|(_, &idx)| idx)
+            .collect();
+
+        if train_idx.is_empty() || test_idx.is_empty() {
+            continue;
+        }
+
+        let train_features: Vec<PredictionFeatures> =
+            train_idx.iter().map(|&i| features[i].clone()).collect();
+        let train_targets: Vec<f64> = train_idx.iter().map(|&i| targets[i]).collect();
+        let test_features: Vec<PredictionFeatures> =
+            test_idx.iter().map(|&i| features[i].clone()).collect();
+        let test_targets: Vec<f64> = test_idx.iter().map(|&i| targets[i]).collect();
+
+        let mut fold_predictor = predictor.clone();
+        fold_predictor.train(&train_features, &train_targets);
+
+        let r2 = r2_of(&fold_predictor, &test_features, &test_targets);
+        let sse: f64 = test_features
+            .iter()
+            .zip(test_targets.iter())
+            .map(|(feat, &target)| (target - fold_predictor.predict(feat)).powi(2))
+            .sum();
+        let rmse = (sse / test_targets.len() as f64).sqrt();
+
+        fold_r2.push(r2);
+        fold_rmse.push(rmse);
+    }
+
+    let (mean_r2, stddev_r2) = mean_and_stddev(&fold_r2);
+    let (mean_rmse, stddev_rmse) = mean_and_stddev(&fold_rmse);
+
+    CrossValidationReport {
+        fold_r2,
+        mean_r2,
+        stddev_r2,
+        mean_rmse,
+        stddev_rmse,
+    }
+}
+
+/// One `PredictionFeatures` field's contribution to a predictor's accuracy,
+/// as ranked by `permutation_importance`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureImportance {
+    pub feature: String,
+    /// Drop in R² when this feature's column is shuffled across the
+    /// dataset; larger means the predictor leans on it more.
+    pub importance: f64,
+}
+
+/// Name of the `PredictionFeatures` field at `feature_vector`'s index.
+fn feature_name(index: usize) -> &'static str {
+    match index {
+        0 => "bitrate_kbps",
+        1 => "resolution_width",
+        2 => "resolution_height",
+        3 => "frame_rate",
+        4 => "frame_drop",
+        5 => "motion_intensity",
+        _ => "unknown",
+    }
+}
+
+/// Overwrite the feature at `feature_vector`'s `index` in place, the inverse
+/// of `feature_vector`, used to splice a shuffled column back into a cloned
+/// `PredictionFeatures`.
+fn set_feature_at(features: &mut PredictionFeatures, index: usize, value: f64) {
+    match index {
+        0 => features.bitrate_kbps = value as f32,
+        1 => features.resolution_width = value as u32,
+        2 => features.resolution_height = value as u32,
+        3 => features.frame_rate = value as f32,
+        4 => features.frame_drop = value as f32,
+        5 => features.motion_intensity = value as f32,
+        _ => {}
+    }
+}
+
+/// Permutation-based feature importance: score `predictor` once on
+/// `features`/`targets`, then for each `PredictionFeatures` field shuffle
+/// that column across the dataset, re-score, and report the R² drop. A
+/// field the model actually relies on (e.g. `bitrate_kbps`) shows up with a
+/// large positive importance; one it ignores scores near zero. Returned
+/// sorted most-important first.
+pub fn permutation_importance<P: Predictor>(
+    predictor: &P,
+    features: &[PredictionFeatures],
+    targets: &[f64],
+) -> Vec<FeatureImportance> {
+    if features.is_empty() || targets.is_empty() {
+        return Vec::new();
+    }
+
+    let baseline_r2 = r2_of(predictor, features, targets);
+    let mut rng = rand::thread_rng();
+
+    let mut importances: Vec<FeatureImportance> = (0..N_FEATURES)
+        .map(|col| {
+            let mut column: Vec<f64> = features.iter().map(|f| feature_vector(f)[col]).collect();
+            column.shuffle(&mut rng);
+
+            let shuffled: Vec<PredictionFeatures> = features
+                .iter()
+                .zip(column.iter())
+                .map(|(feat, &value)| {
+                    let mut feat = feat.clone();
+                    set_feature_at(&mut feat, col, value);
+                    feat
+                })
+                .collect();
+
+            FeatureImportance {
+                feature: feature_name(col).to_string(),
+                importance: baseline_r2 - r2_of(predictor, &shuffled, targets),
+            }
+        })
+        .collect();
+
+    importances.sort_by(|a, b| b.importance.partial_cmp(&a.importance).unwrap());
+    importances
+}
+
+/// Report from `ModelBundle::validate`: cross-validation plus permutation
+/// feature importance for the VMAF forest, the sub-model most directly tied
+/// to delivered quality.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelValidationReport {
+    pub vmaf_cross_validation: CrossValidationReport,
+    pub vmaf_feature_importance: Vec<FeatureImportance>,
+}
+
+impl Default for ModelBundle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModelBundle {
+    /// Create a new model bundle with default initialization
+    pub fn new() -> Self {
+        Self {
+            version: "1.1.0".to_string(),
+            vmaf_model: SimpleRandomForest::new(10),
+            psnr_model: SimpleRandomForest::new(10),
+            ssim_model: SimpleRandomForest::new(10),
+            ms_ssim_model: SimpleRandomForest::new(10),
+            psnr_hvs_model: SimpleRandomForest::new(10),
+            ciede2000_model: SimpleRandomForest::new(10),
+            cost_model: SimpleGradientBoosting::new(0.1),
+            co2_model: SimpleGradientBoosting::new(0.1),
+            online_state: OnlineTrainingState::default(),
+            boosted_vmaf_model: None,
+            boosted_psnr_model: None,
+        }
+    }
+
+    /// Train `GradientBoostedTrees` on `features`/`targets` and substitute
+    /// them for the bagged forest: `predict` checks `boosted_vmaf_model`/
+    /// `boosted_psnr_model` first and only falls back to `vmaf_model`/
+    /// `psnr_model` when the corresponding boosted model is `None`. Pass
+    /// `None` for either target to leave that metric on the forest.
+    #[allow(clippy::too_many_arguments)]
+    pub fn enable_boosted_trees(
+        &mut self,
+        features: &[PredictionFeatures],
+        vmaf_targets: Option<&[f64]>,
+        psnr_targets: Option<&[f64]>,
+        n_trees: usize,
+        learning_rate: f64,
+        max_depth: usize,
+        subsample: f64,
+    ) {
+        if let Some(targets) = vmaf_targets {
+            let mut trees = GradientBoostedTrees::new(n_trees, learning_rate, max_depth, subsample);
+            trees.train(features, targets);
+            self.boosted_vmaf_model = Some(trees);
+        }
+        if let Some(targets) = psnr_targets {
+            let mut trees = GradientBoostedTrees::new(n_trees, learning_rate, max_depth, subsample);
+            trees.train(features, targets);
+            self.boosted_psnr_model = Some(trees);
+        }
+    }
+
+    /// Fold one `(features, targets)` observation into the model with a
+    /// single online step per sub-model, at a learning rate that decays as
+    /// `ηₜ = η₀ / (1 + λ·t)` over the bundle's running observation count.
+    /// VMAF/PSNR update whichever model `predict` actually serves: the bagged
+    /// forest gets its leaves nudged directly, but once `enable_boosted_trees`
+    /// is active, `boosted_vmaf_model`/`boosted_psnr_model` buffer
+    /// observations and grow a new tree instead, the same way cost/CO2
+    /// already do — otherwise the forest would keep "learning" while
+    /// `predict` silently served the now-stale boosted trees. Returns the
+    /// running R² averaged across the four targets, each scored against
+    /// whichever model was actually updated.
+    pub fn update_online(
+        &mut self,
+        features: &PredictionFeatures,
+        vmaf: f32,
+        psnr: f32,
+        cost: f32,
+        co2: f32,
+    ) -> f64 {
+        let eta_t = self.online_state.learning_rate();
+        let batch_size = self.online_state.batch_size;
+
+        let predicted_vmaf = if let Some(trees) = self.boosted_vmaf_model.as_mut() {
+            trees.update_online(&mut self.online_state.boosted_vmaf_buffer, batch_size, features, vmaf as f64);
+            trees.predict(features)
+        } else {
+            self.vmaf_model.update_online(features, vmaf, eta_t);
+            self.vmaf_model.predict(features) as f64
+        };
+        let predicted_psnr = if let Some(trees) = self.boosted_psnr_model.as_mut() {
+            trees.update_online(&mut self.online_state.boosted_psnr_buffer, batch_size, features, psnr as f64);
+            trees.predict(features)
+        } else {
+            self.psnr_model.update_online(features, psnr, eta_t);
+            self.psnr_model.predict(features) as f64
+        };
+
+        self.cost_model
+            .update_online(&mut self.online_state.cost_buffer, batch_size, features, cost as f64);
+        self.co2_model
+            .update_online(&mut self.online_state.co2_buffer, batch_size, features, co2 as f64);
+
+        self.online_state.vmaf_metrics.update(vmaf as f64, predicted_vmaf);
+        self.online_state.psnr_metrics.update(psnr as f64, predicted_psnr);
+        self.online_state.cost_metrics.update(cost as f64, self.cost_model.predict(features) as f64);
+        self.online_state.co2_metrics.update(co2 as f64, self.co2_model.predict(features) as f64);
+        self.online_state.observations_seen += 1;
+
+        let r2s = [
+            self.online_state.vmaf_metrics.r2(),
+            self.online_state.psnr_metrics.r2(),
+            self.online_state.cost_metrics.r2(),
+            self.online_state.co2_metrics.r2(),
+        ];
+        r2s.iter().sum::<f64>() / r2s.len() as f64
+    }
+
+    /// Cross-validate the VMAF forest on held-out folds of `features`/
+    /// `targets` and rank which `PredictionFeatures` field it actually
+    /// relies on, so a freshly trained bundle can be sanity-checked for
+    /// overfitting or a misplaced dependency before `save_model`.
+    pub fn validate(
+        &self,
+        features: &[PredictionFeatures],
+        targets: &[f64],
+        k: usize,
+    ) -> ModelValidationReport {
+        ModelValidationReport {
+            vmaf_cross_validation: cross_validate(&self.vmaf_model, features, targets, k),
+            vmaf_feature_importance: permutation_importance(&self.vmaf_model, features, targets),
+        }
+    }
+}
+
+/// Magic bytes prefixing a binary-encoded `ModelBundle`, used by `load_model`
+/// to tell it apart from pretty-printed JSON.
+const MODEL_BINARY_MAGIC: &[u8; 4] = b"MLBM";
+/// Current binary schema version; bump whenever the bincode layout of
+/// `ModelBundle` changes in a way that isn't backward compatible.
+const MODEL_BINARY_FORMAT_VERSION: u32 = 1;
+
+/// Load model bundle from disk, auto-detecting the binary format (via its
+/// magic header) vs. JSON.
+pub fn load_model(path: &str) -> Result<ModelBundle, String> {
+    let model_path = Path::new(path);
+
+    if !model_path.exists() {
+        // Return a default trained model if file doesn't exist
+        return Ok(create_default_model());
+    }
+
+    let bytes = fs::read(model_path).map_err(|e| format!("Failed to read model file: {}", e))?;
+
+    if is_binary_model(&bytes) {
+        let (_, payload) = split_binary_header(&bytes)?;
+        return bincode::deserialize(payload).map_err(|e| format!("Failed to parse model: {}", e));
+    }
+
+    let content = String::from_utf8(bytes)
+        .map_err(|e| format!("Model file is not valid UTF-8 JSON: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse model: {}", e))
+}
+
+fn is_binary_model(bytes: &[u8]) -> bool {
+    bytes.len() >= MODEL_BINARY_MAGIC.len() && &bytes[..MODEL_BINARY_MAGIC.len()] == MODEL_BINARY_MAGIC
+}
+
+/// Split a binary model file into its validated `format_version` and the
+/// remaining bincode payload, failing loudly on a magic mismatch or an
+/// unsupported version rather than silently mis-parsing a future schema.
+fn split_binary_header(bytes: &[u8]) -> Result<(u32, &[u8]), String> {
+    if bytes.len() < MODEL_BINARY_MAGIC.len() + 4 {
+        return Err("Model file too short for a binary header".to_string());
+    }
+
+    let (magic, rest) = bytes.split_at(MODEL_BINARY_MAGIC.len());
+    if magic != MODEL_BINARY_MAGIC {
+        return Err("Not a recognized binary model file".to_string());
+    }
+
+    let (version_bytes, payload) = rest.split_at(4);
+    let version = u32::from_le_bytes(version_bytes.try_into().unwrap());
+    if version != MODEL_BINARY_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported model binary format version {} (expected {})",
+            version, MODEL_BINARY_FORMAT_VERSION
+        ));
+    }
+
+    Ok((version, payload))
+}
+
+/// Load a model bundle saved by `save_model_binary`.
+pub fn load_model_binary(path: &str) -> Result<ModelBundle, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read model file: {}", e))?;
+    let (_, payload) = split_binary_header(&bytes)?;
+    bincode::deserialize(payload).map_err(|e| format!("Failed to parse model: {}", e))
+}
+
+/// Create a default pre-trained model
+fn create_default_model() -> ModelBundle {
+    let mut model = ModelBundle::new();
+    
+    // Pre-train with synthetic data representing typical scenarios
+    let synthetic_features = vec![
+        PredictionFeatures {
+            bitrate_kbps: 1000.0,
+            resolution_width: 1280,
+            resolution_height: 720,
+            frame_rate: 30.0,
+            frame_drop: 0.01,
+            motion_intensity: 0.5,
+        },
+        PredictionFeatures {
+            bitrate_kbps: 2500.0,
+            resolution_width: 1920,
+            resolution_height: 1080,
+            frame_rate: 30.0,
+            frame_drop: 0.005,
+            motion_intensity: 0.6,
+        },
+        PredictionFeatures {
+            bitrate_kbps: 5000.0,
+            resolution_width: 3840,
+            resolution_height: 2160,
+            frame_rate: 60.0,
+            frame_drop: 0.002,
+            motion_intensity: 0.7,
+        },
+    ];
+
+    let vmaf_targets = vec![75.0, 85.0, 92.0];
+    let psnr_targets = vec![35.0, 38.0, 42.0];
+    let ssim_targets = vec![0.94, 0.97, 0.99];
+    let ms_ssim_targets = vec![0.92, 0.96, 0.98];
+    let psnr_hvs_targets = vec![33.0, 36.5, 40.0];
+    let ciede2000_targets = vec![3.5, 2.0, 0.8];
+    let cost_targets = vec![0.05, 0.12, 0.30];
+    let co2_targets = vec![0.01, 0.025, 0.06];
+
+    model.vmaf_model.train(&synthetic_features, &vmaf_targets);
+    model.psnr_model.train(&synthetic_features, &psnr_targets);
+    model.ssim_model.train(&synthetic_features, &ssim_targets);
+    model.ms_ssim_model.train(&synthetic_features, &ms_ssim_targets);
+    model.psnr_hvs_model.train(&synthetic_features, &psnr_hvs_targets);
+    model.ciede2000_model.train(&synthetic_features, &ciede2000_targets);
+    model.cost_model.train(&synthetic_features, &cost_targets);
+    model.co2_model.train(&synthetic_features, &co2_targets);
+
+    model
+}
+
+/// Make prediction using the model bundle
+pub fn predict(features: &PredictionFeatures, model: &ModelBundle) -> PredictionResult {
+    let predicted_vmaf = model
+        .boosted_vmaf_model
+        .as_ref()
+        .map(|trees| trees.predict(features) as f32)
+        .unwrap_or_else(|| model.vmaf_model.predict(features));
+    let predicted_psnr = model
+        .boosted_psnr_model
+        .as_ref()
+        .map(|trees| trees.predict(features) as f32)
+        .unwrap_or_else(|| model.psnr_model.predict(features));
+    let predicted_ssim = model.ssim_model.predict(features);
+    let predicted_ms_ssim = model.ms_ssim_model.predict(features);
+    let predicted_psnr_hvs = model.psnr_hvs_model.predict(features);
+    let predicted_ciede2000 = model.ciede2000_model.predict(features);
+    let predicted_cost_usd = model.cost_model.predict(features);
+    let predicted_co2_kg = model.co2_model.predict(features);
+
+    // Calculate confidence based on feature quality
+    let confidence = calculate_confidence(features, predicted_vmaf);
+
+    // Recommend bitrate based on predictions
+    let recommended_bitrate_kbps = recommend_bitrate(features, predicted_vmaf, predicted_cost_usd);
+
+    PredictionResult {
+        predicted_vmaf,
+        predicted_psnr,
+        predicted_ssim,
+        predicted_ms_ssim,
+        predicted_psnr_hvs,
+        predicted_ciede2000,
+        predicted_cost_usd,
+        predicted_co2_kg,
+        confidence,
+        recommended_bitrate_kbps,
+    }
+}
+
+/// Calculate prediction confidence
+fn calculate_confidence(features: &PredictionFeatures, predicted_vmaf: f32) -> f32 {
+    let mut confidence: f32 = 0.8; // Base confidence
+
+    // Adjust based on feature quality
+    if features.frame_drop < 0.01 {
+        confidence += 0.1;
+    } else if features.frame_drop > 0.05 {
+        confidence -= 0.2;
+    }
+
+    if predicted_vmaf > 80.0 {
+        confidence += 0.05;
+    } else if predicted_vmaf < 60.0 {
+        confidence -= 0.1;
+    }
+
+    confidence.clamp(0.0, 1.0)
 }
 
 /// Recommend optimal bitrate
@@ -365,11 +1462,209 @@ fn recommend_bitrate(features: &PredictionFeatures, predicted_vmaf: f32, _cost:
     }
 }
 
-/// Retrain models with new dataset (simplified for now)
-pub fn retrain(_features: &[PredictionFeatures], _targets_vmaf: &[f32], _targets_psnr: &[f32], _targets_cost: &[f32], _targets_co2: &[f32]) -> ModelBundle {
-    // For now, return a new default model
-    // In production, this would use the provided data for training
-    create_default_model()
+/// Like `recommend_bitrate`, but informed by a full per-frame VMAF
+/// distribution rather than a single scalar prediction: blends the harmonic
+/// mean and 1st-percentile "worst moment" frames instead of the arithmetic
+/// mean, so an encode that looks fine on average but stutters through a few
+/// rough frames gets bumped up anyway.
+pub fn recommend_bitrate_from_distribution(
+    features: &PredictionFeatures,
+    summary: &VmafDistributionSummary,
+    cost: f32,
+) -> u32 {
+    let worst_case_vmaf = (0.5 * summary.harmonic_mean + 0.5 * summary.p1) as f32;
+    recommend_bitrate(features, worst_case_vmaf, cost)
+}
+
+/// Lowest rung of the bitrate ladder `recommend_bitrate_constrained` sweeps.
+const BITRATE_SWEEP_MIN_KBPS: f64 = 200.0;
+/// Highest rung of the bitrate ladder `recommend_bitrate_constrained` sweeps.
+const BITRATE_SWEEP_MAX_KBPS: f64 = 20_000.0;
+/// Number of rungs in the geometric bitrate ladder.
+const BITRATE_SWEEP_RUNGS: usize = 24;
+
+/// One rung of the bitrate sweep: the candidate bitrate plus its predicted
+/// VMAF/cost/CO2 with every other feature held fixed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BitrateCandidate {
+    pub bitrate_kbps: u32,
+    pub predicted_vmaf: f32,
+    pub predicted_cost_usd: f32,
+    pub predicted_co2_kg: f32,
+}
+
+/// Result of `recommend_bitrate_constrained`: the chosen rung (`None` if no
+/// candidate satisfied every constraint) plus every swept rung in ladder
+/// order (`pareto_front`) so callers can pick their own operating point;
+/// dominated rungs are not filtered out of it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstrainedBitrateResult {
+    pub chosen: Option<BitrateCandidate>,
+    pub pareto_front: Vec<BitrateCandidate>,
+}
+
+/// Sweep the shared geometric bitrate ladder from 200 kbps to 20 Mbps,
+/// running the full `predict` at each rung with every other feature held
+/// fixed. Factored out so `recommend_bitrate_constrained` and `simulate`
+/// sweep the exact same rungs instead of maintaining two copies of the
+/// ladder formula.
+fn bitrate_ladder(features: &PredictionFeatures, model: &ModelBundle) -> Vec<(u32, PredictionResult)> {
+    let ratio = (BITRATE_SWEEP_MAX_KBPS / BITRATE_SWEEP_MIN_KBPS)
+        .powf(1.0 / (BITRATE_SWEEP_RUNGS as f64 - 1.0));
+
+    (0..BITRATE_SWEEP_RUNGS)
+        .map(|rung| {
+            let bitrate_kbps = (BITRATE_SWEEP_MIN_KBPS * ratio.powi(rung as i32)).round() as u32;
+            let rung_features = PredictionFeatures {
+                bitrate_kbps: bitrate_kbps as f32,
+                ..features.clone()
+            };
+            (bitrate_kbps, predict(&rung_features, model))
+        })
+        .collect()
+}
+
+/// Sweep a geometric bitrate ladder from 200 kbps to 20 Mbps, running the
+/// full `predict` at each rung with every other feature held fixed, and pick
+/// the rung maximizing predicted VMAF subject to `max_cost_usd`,
+/// `max_co2_kg`, and a `min_vmaf` floor. Returns the whole swept front
+/// alongside the chosen rung so callers can inspect the cost/quality
+/// trade-off curve themselves.
+pub fn recommend_bitrate_constrained(
+    features: &PredictionFeatures,
+    model: &ModelBundle,
+    max_cost_usd: f32,
+    max_co2_kg: f32,
+    min_vmaf: f32,
+) -> ConstrainedBitrateResult {
+    let pareto_front: Vec<BitrateCandidate> = bitrate_ladder(features, model)
+        .into_iter()
+        .map(|(bitrate_kbps, result)| BitrateCandidate {
+            bitrate_kbps,
+            predicted_vmaf: result.predicted_vmaf,
+            predicted_cost_usd: result.predicted_cost_usd,
+            predicted_co2_kg: result.predicted_co2_kg,
+        })
+        .collect();
+
+    let chosen = pareto_front
+        .iter()
+        .filter(|c| {
+            c.predicted_cost_usd <= max_cost_usd
+                && c.predicted_co2_kg <= max_co2_kg
+                && c.predicted_vmaf >= min_vmaf
+        })
+        .max_by(|a, b| a.predicted_vmaf.partial_cmp(&b.predicted_vmaf).unwrap())
+        .copied();
+
+    ConstrainedBitrateResult { chosen, pareto_front }
+}
+
+/// One rung of a full encoding-session simulation: the candidate bitrate
+/// plus its predicted quality and its cost/CO2 projected across the whole
+/// session rather than a single instant.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SessionLadderRung {
+    pub bitrate_kbps: u32,
+    pub predicted_vmaf: f32,
+    pub session_cost_usd: f64,
+    pub session_co2_kg: f64,
+}
+
+/// Result of `simulate`: the full swept ladder plus the minimal-cost rung
+/// that still clears the session's quality floor, if any does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSimulation {
+    pub ladder: Vec<SessionLadderRung>,
+    pub chosen: Option<SessionLadderRung>,
+}
+
+/// Simulate a full encoding session rather than a single instant: sweep the
+/// same geometric bitrate ladder as `recommend_bitrate_constrained`, project
+/// each rung's predicted cost and CO2 across `session_hours` (the ML
+/// cost/CO2 models predict a per-hour rate; `cost_model`'s fixed compute
+/// overhead and `pricing`'s regional carbon intensity scale the rest), and
+/// pick the minimal-cost rung whose predicted VMAF still clears `min_vmaf`.
+/// This is the same "optimal retention" search as a subscriber funnel,
+/// applied to an encoding budget instead: reject every rung below the
+/// quality floor, then take the cheapest survivor.
+pub fn simulate(
+    features: &PredictionFeatures,
+    model: &ModelBundle,
+    session_hours: f64,
+    cost_model: &CostModel,
+    pricing: &RegionalPricing,
+    min_vmaf: f32,
+) -> SessionSimulation {
+    let baseline_carbon_intensity = RegionalPricing::new("us-east-1").carbon_intensity;
+
+    let ladder: Vec<SessionLadderRung> = bitrate_ladder(features, model)
+        .into_iter()
+        .map(|(bitrate_kbps, result)| {
+            let session_cost_usd = result.predicted_cost_usd as f64 * session_hours
+                + cost_model.cpu_cost_per_hour * session_hours;
+            let session_co2_kg = result.predicted_co2_kg as f64
+                * session_hours
+                * (pricing.carbon_intensity / baseline_carbon_intensity);
+
+            SessionLadderRung {
+                bitrate_kbps,
+                predicted_vmaf: result.predicted_vmaf,
+                session_cost_usd,
+                session_co2_kg,
+            }
+        })
+        .collect();
+
+    let chosen = ladder
+        .iter()
+        .filter(|rung| rung.predicted_vmaf >= min_vmaf)
+        .min_by(|a, b| a.session_cost_usd.partial_cmp(&b.session_cost_usd).unwrap())
+        .cloned();
+
+    SessionSimulation { ladder, chosen }
+}
+
+/// Full batch retrain: fits fresh models directly from the given dataset
+/// instead of discarding it, covering all eight predicted metrics (not just
+/// VMAF/PSNR/cost/CO2 — SSIM/MS-SSIM/PSNR-HVS/CIEDE2000 would otherwise stay
+/// stuck on `ModelBundle::new()`'s untrained forest forever, since
+/// `create_default_model`'s synthetic fixture is the only other place that
+/// ever trains them). For adapting an already-deployed model to a steady
+/// trickle of new measurements, prefer `ModelBundle::update_online`.
+///
+/// `boosted_vmaf_model`/`boosted_psnr_model` are not retrained here: they're
+/// an opt-in override with their own hyperparameters (tree count, learning
+/// rate, depth, subsample), so callers who enabled them should re-run
+/// `ModelBundle::enable_boosted_trees` afterward with the same dataset.
+///
+/// Rust-only for now, same as before this fix: unlike `ml_update_online`,
+/// there is no `ml_retrain_model` FFI wrapper yet, so FFmpeg-side callers
+/// can't batch-retrain directly.
+#[allow(clippy::too_many_arguments)]
+pub fn retrain(
+    features: &[PredictionFeatures],
+    targets_vmaf: &[f32],
+    targets_psnr: &[f32],
+    targets_ssim: &[f32],
+    targets_ms_ssim: &[f32],
+    targets_psnr_hvs: &[f32],
+    targets_ciede2000: &[f32],
+    targets_cost: &[f32],
+    targets_co2: &[f32],
+) -> ModelBundle {
+    let mut model = ModelBundle::new();
+    model.vmaf_model.train(features, targets_vmaf);
+    model.psnr_model.train(features, targets_psnr);
+    model.ssim_model.train(features, targets_ssim);
+    model.ms_ssim_model.train(features, targets_ms_ssim);
+    model.psnr_hvs_model.train(features, targets_psnr_hvs);
+    model.ciede2000_model.train(features, targets_ciede2000);
+    model.cost_model.train(features, targets_cost);
+    model.co2_model.train(features, targets_co2);
+    model
 }
 
 /// Save model bundle to disk
@@ -385,10 +1680,32 @@ pub fn save_model(model: &ModelBundle, path: &str) -> Result<(), String> {
 
     fs::write(path, json)
         .map_err(|e| format!("Failed to write model file: {}", e))?;
-    
+
     Ok(())
 }
 
+/// Save model bundle to disk using the compact binary format: a 4-byte
+/// magic tag, a little-endian `u32` format version, then the
+/// bincode-serialized bundle. Smaller and faster to (de)serialize than the
+/// pretty-printed JSON, which matters when the model is embedded directly
+/// in the C-linked FFmpeg binary.
+pub fn save_model_binary(model: &ModelBundle, path: &str) -> Result<(), String> {
+    let payload = bincode::serialize(model)
+        .map_err(|e| format!("Failed to serialize model: {}", e))?;
+
+    if let Some(parent) = Path::new(path).parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let mut bytes = Vec::with_capacity(MODEL_BINARY_MAGIC.len() + 4 + payload.len());
+    bytes.extend_from_slice(MODEL_BINARY_MAGIC);
+    bytes.extend_from_slice(&MODEL_BINARY_FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&payload);
+
+    fs::write(path, bytes).map_err(|e| format!("Failed to write model file: {}", e))
+}
+
 // ============================================================================
 // Legacy Models (kept for backward compatibility)
 // ============================================================================
@@ -401,6 +1718,12 @@ pub struct LinearPredictor {
     r2: f64,
 }
 
+impl Default for LinearPredictor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LinearPredictor {
     /// Create new predictor
     pub fn new() -> Self {
@@ -465,18 +1788,308 @@ impl LinearPredictor {
     }
 }
 
-/// Cost model for transcoding
+/// Multivariate ridge regression over all six `PredictionFeatures`, solving
+/// the normal equations `w = (XᵀX + αI)⁻¹Xᵀy` so correlated features (e.g.
+/// resolution and motion intensity) aren't dropped the way single-variable
+/// `LinearPredictor` drops them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CostModel {
-    pub energy_cost_per_kwh: f64,
-    pub cpu_cost_per_hour: f64,
+pub struct MultiLinearPredictor {
+    /// L2 regularization strength keeping `XᵀX` invertible under collinear
+    /// features.
+    pub alpha: f64,
+    pub coefficients: [f64; N_FEATURES],
+    pub intercept: f64,
+    feature_means: [f64; N_FEATURES],
+    feature_std_devs: [f64; N_FEATURES],
+    r2: f64,
 }
 
-impl CostModel {
-    pub fn new(energy_cost_per_kwh: f64, cpu_cost_per_hour: f64) -> Self {
+impl MultiLinearPredictor {
+    pub fn new(alpha: f64) -> Self {
         Self {
-            energy_cost_per_kwh,
-            cpu_cost_per_hour,
+            alpha,
+            coefficients: [0.0; N_FEATURES],
+            intercept: 0.0,
+            feature_means: [0.0; N_FEATURES],
+            feature_std_devs: [1.0; N_FEATURES],
+            r2: 0.0,
+        }
+    }
+
+    /// Fit via ridge regression on standardized features: each column is
+    /// scaled to zero mean/unit variance before solving so the coefficients
+    /// are comparable and the regularization is applied evenly, then
+    /// `predict` un-scales using the stored `feature_means`/`feature_std_devs`.
+    pub fn fit(&mut self, features: &[PredictionFeatures], targets: &[f64]) -> Result<(), String> {
+        if features.len() != targets.len() || features.is_empty() {
+            return Err("Invalid input".to_string());
+        }
+
+        let n = features.len() as f64;
+        let raw: Vec<[f64; N_FEATURES]> = features.iter().map(feature_vector).collect();
+
+        for j in 0..N_FEATURES {
+            let mean = raw.iter().map(|r| r[j]).sum::<f64>() / n;
+            let variance = raw.iter().map(|r| (r[j] - mean).powi(2)).sum::<f64>() / n;
+            let std_dev = variance.sqrt();
+            self.feature_means[j] = mean;
+            self.feature_std_devs[j] = if std_dev > 1e-12 { std_dev } else { 1.0 };
+        }
+
+        let standardized: Vec<[f64; N_FEATURES]> = raw
+            .iter()
+            .map(|row| {
+                let mut z = [0.0; N_FEATURES];
+                for j in 0..N_FEATURES {
+                    z[j] = (row[j] - self.feature_means[j]) / self.feature_std_devs[j];
+                }
+                z
+            })
+            .collect();
+
+        let target_mean = targets.iter().sum::<f64>() / n;
+        let centered_targets: Vec<f64> = targets.iter().map(|&y| y - target_mean).collect();
+
+        // Normal equations over the standardized, mean-centered data:
+        // (XᵀX + αI) w = Xᵀy
+        let mut xtx = [[0.0; N_FEATURES]; N_FEATURES];
+        let mut xty = [0.0; N_FEATURES];
+        for (row, &y) in standardized.iter().zip(centered_targets.iter()) {
+            for i in 0..N_FEATURES {
+                xty[i] += row[i] * y;
+                for j in 0..N_FEATURES {
+                    xtx[i][j] += row[i] * row[j];
+                }
+            }
+        }
+        for (i, row) in xtx.iter_mut().enumerate() {
+            row[i] += self.alpha;
+        }
+
+        self.coefficients = solve_linear_system(xtx, xty)
+            .ok_or_else(|| "Normal equations are singular even with ridge regularization".to_string())?;
+        self.intercept = target_mean;
+
+        let mut ss_tot = 0.0;
+        let mut ss_res = 0.0;
+        for (feat, &y) in features.iter().zip(targets.iter()) {
+            let pred = self.predict(feat);
+            ss_tot += (y - target_mean).powi(2);
+            ss_res += (y - pred).powi(2);
+        }
+        self.r2 = if ss_tot > 0.0 { 1.0 - ss_res / ss_tot } else { 0.0 };
+
+        Ok(())
+    }
+
+    pub fn predict(&self, features: &PredictionFeatures) -> f64 {
+        let raw = feature_vector(features);
+        let mut pred = self.intercept;
+        for (j, &x) in raw.iter().enumerate() {
+            let z = (x - self.feature_means[j]) / self.feature_std_devs[j];
+            pred += self.coefficients[j] * z;
+        }
+        pred.max(0.0)
+    }
+
+    pub fn r2_score(&self) -> f64 {
+        self.r2
+    }
+}
+
+/// Solve `a·x = b` via Gauss-Jordan elimination with partial pivoting.
+/// Returns `None` if `a` is numerically singular.
+fn solve_linear_system(
+    mut a: [[f64; N_FEATURES]; N_FEATURES],
+    mut b: [f64; N_FEATURES],
+) -> Option<[f64; N_FEATURES]> {
+    for col in 0..N_FEATURES {
+        let pivot_row = (col..N_FEATURES)
+            .max_by(|&r1, &r2| a[r1][col].abs().partial_cmp(&a[r2][col].abs()).unwrap())?;
+
+        if a[pivot_row][col].abs() < 1e-12 {
+            return None;
+        }
+
+        a.swap(col, pivot_row);
+        b.swap(col, pivot_row);
+
+        let pivot = a[col][col];
+        for x in a[col].iter_mut().skip(col) {
+            *x /= pivot;
+        }
+        b[col] /= pivot;
+
+        for row in 0..N_FEATURES {
+            if row == col {
+                continue;
+            }
+            let factor = a[row][col];
+            let pivot_row = a[col];
+            for (x, p) in a[row].iter_mut().zip(pivot_row.iter()).skip(col) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    Some(b)
+}
+
+/// Elastic-net regression over all six `PredictionFeatures` via cyclic
+/// coordinate descent, minimizing
+/// `(1/2n)||y - Xw||² + α(l1_ratio·||w||₁ + ((1−l1_ratio)/2)||w||₂²)`.
+/// Unlike the pure-ridge `MultiLinearPredictor`, the L1 term can zero out
+/// irrelevant features (e.g. `frame_rate` when it doesn't move the target),
+/// making the fit both more interpretable and more robust on small
+/// training sets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElasticNetPredictor {
+    pub alpha: f64,
+    pub l1_ratio: f64,
+    pub max_iterations: usize,
+    pub tolerance: f64,
+    pub coefficients: [f64; N_FEATURES],
+    pub intercept: f64,
+    feature_means: [f64; N_FEATURES],
+    feature_std_devs: [f64; N_FEATURES],
+    r2: f64,
+}
+
+impl ElasticNetPredictor {
+    pub fn new(alpha: f64, l1_ratio: f64) -> Self {
+        Self {
+            alpha,
+            l1_ratio: l1_ratio.clamp(0.0, 1.0),
+            max_iterations: 1000,
+            tolerance: 1e-6,
+            coefficients: [0.0; N_FEATURES],
+            intercept: 0.0,
+            feature_means: [0.0; N_FEATURES],
+            feature_std_devs: [1.0; N_FEATURES],
+            r2: 0.0,
+        }
+    }
+
+    /// Standardize each feature column, then cyclically update one
+    /// coefficient at a time via the soft-thresholded closed form
+    /// `w_j = S(ρ_j, α·l1_ratio) / (z_j + α(1−l1_ratio))`, maintaining a
+    /// running residual so each update is O(n) rather than re-scoring the
+    /// whole model. Stops once the largest per-iteration coefficient move
+    /// drops below `tolerance` (a cheap proxy for the duality gap) or
+    /// `max_iterations` is reached.
+    pub fn fit(&mut self, features: &[PredictionFeatures], targets: &[f64]) -> Result<(), String> {
+        if features.len() != targets.len() || features.is_empty() {
+            return Err("Invalid input".to_string());
+        }
+
+        let n = features.len();
+        let n_f = n as f64;
+        let raw: Vec<[f64; N_FEATURES]> = features.iter().map(feature_vector).collect();
+
+        for j in 0..N_FEATURES {
+            let mean = raw.iter().map(|r| r[j]).sum::<f64>() / n_f;
+            let variance = raw.iter().map(|r| (r[j] - mean).powi(2)).sum::<f64>() / n_f;
+            let std_dev = variance.sqrt();
+            self.feature_means[j] = mean;
+            self.feature_std_devs[j] = if std_dev > 1e-12 { std_dev } else { 1.0 };
+        }
+
+        let x: Vec<[f64; N_FEATURES]> = raw
+            .iter()
+            .map(|row| {
+                let mut z = [0.0; N_FEATURES];
+                for j in 0..N_FEATURES {
+                    z[j] = (row[j] - self.feature_means[j]) / self.feature_std_devs[j];
+                }
+                z
+            })
+            .collect();
+
+        let target_mean = targets.iter().sum::<f64>() / n_f;
+        let y: Vec<f64> = targets.iter().map(|&t| t - target_mean).collect();
+
+        // z_j = Σx_ij²/n, the per-feature column scale in the coordinate update.
+        let z_j: Vec<f64> = (0..N_FEATURES)
+            .map(|j| x.iter().map(|row| row[j] * row[j]).sum::<f64>() / n_f)
+            .collect();
+
+        let l1 = self.alpha * self.l1_ratio;
+        let l2 = self.alpha * (1.0 - self.l1_ratio);
+
+        let mut w = [0.0_f64; N_FEATURES];
+        let mut residuals = y.clone();
+
+        for _ in 0..self.max_iterations {
+            let mut max_coef_change = 0.0_f64;
+
+            for j in 0..N_FEATURES {
+                let rho: f64 = (0..n).map(|i| x[i][j] * (residuals[i] + x[i][j] * w[j])).sum::<f64>() / n_f;
+                let new_w_j = soft_threshold(rho, l1) / (z_j[j] + l2);
+                let delta = new_w_j - w[j];
+
+                if delta != 0.0 {
+                    for i in 0..n {
+                        residuals[i] -= x[i][j] * delta;
+                    }
+                }
+
+                max_coef_change = max_coef_change.max(delta.abs());
+                w[j] = new_w_j;
+            }
+
+            if max_coef_change < self.tolerance {
+                break;
+            }
+        }
+
+        self.coefficients = w;
+        self.intercept = target_mean;
+
+        let mut ss_tot = 0.0;
+        let mut ss_res = 0.0;
+        for (feat, &target) in features.iter().zip(targets.iter()) {
+            let pred = self.predict(feat);
+            ss_tot += (target - target_mean).powi(2);
+            ss_res += (target - pred).powi(2);
+        }
+        self.r2 = if ss_tot > 0.0 { 1.0 - ss_res / ss_tot } else { 0.0 };
+
+        Ok(())
+    }
+
+    pub fn predict(&self, features: &PredictionFeatures) -> f64 {
+        let raw = feature_vector(features);
+        let mut pred = self.intercept;
+        for (j, &x) in raw.iter().enumerate() {
+            let z = (x - self.feature_means[j]) / self.feature_std_devs[j];
+            pred += self.coefficients[j] * z;
+        }
+        pred.max(0.0)
+    }
+
+    pub fn r2_score(&self) -> f64 {
+        self.r2
+    }
+}
+
+/// Soft-thresholding operator `S(a, γ) = sign(a)·max(|a| − γ, 0)`.
+fn soft_threshold(value: f64, gamma: f64) -> f64 {
+    value.signum() * (value.abs() - gamma).max(0.0)
+}
+
+/// Cost model for transcoding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostModel {
+    pub energy_cost_per_kwh: f64,
+    pub cpu_cost_per_hour: f64,
+}
+
+impl CostModel {
+    pub fn new(energy_cost_per_kwh: f64, cpu_cost_per_hour: f64) -> Self {
+        Self {
+            energy_cost_per_kwh,
+            cpu_cost_per_hour,
         }
     }
 
@@ -543,73 +2156,413 @@ impl RegionalPricing {
     }
 }
 
-// ============================================================================
-// C FFI for ML Prediction (New)
-// ============================================================================
-
+// ============================================================================
+// C FFI for ML Prediction (New)
+// ============================================================================
+
+#[repr(C)]
+pub struct CModelBundle {
+    inner: ModelBundle,
+}
+
+/// # Safety
+///
+/// `path` must be null (the default path is used) or a valid, NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn ml_load_model(path: *const c_char) -> *mut CModelBundle {
+    let path_str = if path.is_null() {
+        "./ml_models/model.json"
+    } else {
+        unsafe {
+            CStr::from_ptr(path).to_str().unwrap_or("./ml_models/model.json")
+        }
+    };
+
+    match load_model(path_str) {
+        Ok(model) => Box::into_raw(Box::new(CModelBundle { inner: model })),
+        Err(_) => {
+            // Return default model on error
+            Box::into_raw(Box::new(CModelBundle { inner: create_default_model() }))
+        }
+    }
+}
+
+/// # Safety
+///
+/// `model_ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types; `result` must be null or point to writable space for one `PredictionResult`.
+#[no_mangle]
+pub unsafe extern "C" fn ml_predict(
+    model_ptr: *const CModelBundle,
+    features: *const PredictionFeatures,
+    result: *mut PredictionResult,
+) -> i32 {
+    if model_ptr.is_null() || features.is_null() || result.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let model = &(*model_ptr).inner;
+        let features_ref = &*features;
+        let pred = predict(features_ref, model);
+        *result = pred;
+    }
+
+    0
+}
+
+/// # Safety
+///
+/// `model_ptr` must be null or point to a live `CModelBundle`; `path` must be null or a valid,
+/// NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn ml_save_model(model_ptr: *const CModelBundle, path: *const c_char) -> i32 {
+    if model_ptr.is_null() || path.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let model = &(*model_ptr).inner;
+        let path_str = CStr::from_ptr(path).to_str().unwrap_or("./ml_models/model.json");
+
+        match save_model(model, path_str) {
+            Ok(_) => 0,
+            Err(_) => -1,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `model_ptr` must be null or point to a live `CModelBundle`; `path` must be null or a valid,
+/// NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn ml_save_model_binary(model_ptr: *const CModelBundle, path: *const c_char) -> i32 {
+    if model_ptr.is_null() || path.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let model = &(*model_ptr).inner;
+        let path_str = CStr::from_ptr(path).to_str().unwrap_or("./ml_models/model.bin");
+
+        match save_model_binary(model, path_str) {
+            Ok(_) => 0,
+            Err(_) => -1,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `path` must be null (the default path is used) or a valid, NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn ml_load_model_binary(path: *const c_char) -> *mut CModelBundle {
+    let path_str = if path.is_null() {
+        "./ml_models/model.bin"
+    } else {
+        unsafe {
+            CStr::from_ptr(path).to_str().unwrap_or("./ml_models/model.bin")
+        }
+    };
+
+    match load_model_binary(path_str) {
+        Ok(model) => Box::into_raw(Box::new(CModelBundle { inner: model })),
+        Err(_) => Box::into_raw(Box::new(CModelBundle { inner: create_default_model() })),
+    }
+}
+
+/// Fold one live observation into the model in place and report the
+/// running R² averaged across the four targets, so an FFmpeg process can
+/// keep refining predictions from its own encode results without
+/// restarting.
+/// # Safety
+///
+/// `model_ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ml_update_online(
+    model_ptr: *mut CModelBundle,
+    features: *const PredictionFeatures,
+    vmaf: f32,
+    psnr: f32,
+    cost: f32,
+    co2: f32,
+) -> f64 {
+    if model_ptr.is_null() || features.is_null() {
+        return 0.0;
+    }
+
+    unsafe {
+        let model = &mut (*model_ptr).inner;
+        let features_ref = &*features;
+        model.update_online(features_ref, vmaf, psnr, cost, co2)
+    }
+}
+
+/// Sweep the bitrate ladder under the given cost/CO2/VMAF constraints,
+/// writing up to `front_buf_len` `BitrateCandidate`s into `front_buf` and the
+/// chosen rung (0 if none satisfied the constraints) into
+/// `chosen_bitrate_kbps`. Returns the full sweep length (which may exceed
+/// `front_buf_len`; only the first `front_buf_len` rungs are copied), or -1
+/// on a null model/features pointer.
+/// # Safety
+///
+/// `model_ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types; `chosen_bitrate_kbps` must be null or writable; `front_buf` must be null or
+/// point to at least `front_buf_len` writable `BitrateCandidate` slots.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ml_recommend_bitrate_constrained(
+    model_ptr: *const CModelBundle,
+    features: *const PredictionFeatures,
+    max_cost_usd: f32,
+    max_co2_kg: f32,
+    min_vmaf: f32,
+    chosen_bitrate_kbps: *mut u32,
+    front_buf: *mut BitrateCandidate,
+    front_buf_len: usize,
+) -> i32 {
+    if model_ptr.is_null() || features.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let model = &(*model_ptr).inner;
+        let features_ref = &*features;
+        let result = recommend_bitrate_constrained(features_ref, model, max_cost_usd, max_co2_kg, min_vmaf);
+
+        if !chosen_bitrate_kbps.is_null() {
+            *chosen_bitrate_kbps = result.chosen.map(|c| c.bitrate_kbps).unwrap_or(0);
+        }
+
+        if !front_buf.is_null() && front_buf_len > 0 {
+            let copy_len = result.pareto_front.len().min(front_buf_len);
+            std::ptr::copy_nonoverlapping(result.pareto_front.as_ptr(), front_buf, copy_len);
+        }
+
+        result.pareto_front.len() as i32
+    }
+}
+
+/// Simulate a full encoding session under the given regional cost/CO2
+/// inputs, writing up to `ladder_buf_len` `SessionLadderRung`s into
+/// `ladder_buf` and the chosen rung (0 if none cleared `min_vmaf`) into
+/// `chosen_bitrate_kbps`. Returns the full sweep length (which may exceed
+/// `ladder_buf_len`, the same truncate-and-report-full-size convention as
+/// `ml_recommend_bitrate_constrained`), or -1 on a null pointer.
+/// # Safety
+///
+/// `model_ptr`, `features`, `cost_model_ptr` and `pricing_ptr` must be null or point to live,
+/// validly-aligned values of their respective types; `chosen_bitrate_kbps` must be null or
+/// writable; `ladder_buf` must be null or point to at least `ladder_buf_len` writable
+/// `SessionLadderRung` slots.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ml_simulate_session(
+    model_ptr: *const CModelBundle,
+    features: *const PredictionFeatures,
+    session_hours: f64,
+    cost_model_ptr: *const CCostModel,
+    pricing_ptr: *const CRegionalPricing,
+    min_vmaf: f32,
+    chosen_bitrate_kbps: *mut u32,
+    ladder_buf: *mut SessionLadderRung,
+    ladder_buf_len: usize,
+) -> i32 {
+    if model_ptr.is_null() || features.is_null() || cost_model_ptr.is_null() || pricing_ptr.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let model = &(*model_ptr).inner;
+        let features_ref = &*features;
+        let cost_model = &(*cost_model_ptr).inner;
+        let pricing = &(*pricing_ptr).inner;
+        let result = simulate(features_ref, model, session_hours, cost_model, pricing, min_vmaf);
+
+        if !chosen_bitrate_kbps.is_null() {
+            *chosen_bitrate_kbps = result.chosen.map(|r| r.bitrate_kbps).unwrap_or(0);
+        }
+
+        if !ladder_buf.is_null() && ladder_buf_len > 0 {
+            let copy_len = result.ladder.len().min(ladder_buf_len);
+            std::ptr::copy_nonoverlapping(result.ladder.as_ptr(), ladder_buf, copy_len);
+        }
+
+        result.ladder.len() as i32
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `ml_load_model`/`ml_load_model_binary`,
+/// and must not be used again after this call.
+#[no_mangle]
+pub unsafe extern "C" fn ml_model_free(ptr: *mut CModelBundle) {
+    if !ptr.is_null() {
+        unsafe {
+            drop(Box::from_raw(ptr));
+        }
+    }
+}
+
+// ============================================================================
+// C FFI for Go integration (Legacy)
+// ============================================================================
+
+#[repr(C)]
+pub struct CLinearPredictor {
+    inner: LinearPredictor,
+}
+
+/// # Safety
+///
+/// Safe to call with any arguments; marked `unsafe` only because it is exposed via the C ABI.
+#[no_mangle]
+pub unsafe extern "C" fn linear_predictor_new() -> *mut CLinearPredictor {
+    Box::into_raw(Box::new(CLinearPredictor {
+        inner: LinearPredictor::new(),
+    }))
+}
+
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CLinearPredictor`; `x` and `y` must be null or point
+/// to at least `n` valid `f64`s.
+#[no_mangle]
+pub unsafe extern "C" fn linear_predictor_fit(
+    ptr: *mut CLinearPredictor,
+    x: *const f64,
+    y: *const f64,
+    n: usize,
+) -> i32 {
+    if ptr.is_null() || x.is_null() || y.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let predictor = &mut (*ptr).inner;
+        let x_slice = std::slice::from_raw_parts(x, n);
+        let y_slice = std::slice::from_raw_parts(y, n);
+
+        match predictor.fit(x_slice, y_slice) {
+            Ok(_) => 0,
+            Err(_) => -1,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CLinearPredictor`.
+#[no_mangle]
+pub unsafe extern "C" fn linear_predictor_predict(ptr: *const CLinearPredictor, x: f64) -> f64 {
+    if ptr.is_null() {
+        return 0.0;
+    }
+    unsafe { (*ptr).inner.predict(x) }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CLinearPredictor`.
+#[no_mangle]
+pub unsafe extern "C" fn linear_predictor_r2(ptr: *const CLinearPredictor) -> f64 {
+    if ptr.is_null() {
+        return 0.0;
+    }
+    unsafe { (*ptr).inner.r2_score() }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `linear_predictor_new`, and must not be
+/// used again after this call.
+#[no_mangle]
+pub unsafe extern "C" fn linear_predictor_free(ptr: *mut CLinearPredictor) {
+    if !ptr.is_null() {
+        unsafe {
+            drop(Box::from_raw(ptr));
+        }
+    }
+}
+
 #[repr(C)]
-pub struct CModelBundle {
-    inner: ModelBundle,
+pub struct CMultiLinearPredictor {
+    inner: MultiLinearPredictor,
 }
 
+/// # Safety
+///
+/// Safe to call with any arguments; marked `unsafe` only because it is exposed via the C ABI.
 #[no_mangle]
-pub extern "C" fn ml_load_model(path: *const c_char) -> *mut CModelBundle {
-    let path_str = if path.is_null() {
-        "./ml_models/model.json"
-    } else {
-        unsafe {
-            CStr::from_ptr(path).to_str().unwrap_or("./ml_models/model.json")
-        }
-    };
-
-    match load_model(path_str) {
-        Ok(model) => Box::into_raw(Box::new(CModelBundle { inner: model })),
-        Err(_) => {
-            // Return default model on error
-            Box::into_raw(Box::new(CModelBundle { inner: create_default_model() }))
-        }
-    }
+pub unsafe extern "C" fn multilinear_predictor_new(alpha: f64) -> *mut CMultiLinearPredictor {
+    Box::into_raw(Box::new(CMultiLinearPredictor {
+        inner: MultiLinearPredictor::new(alpha),
+    }))
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CMultiLinearPredictor`; `features` and `targets` must
+/// be null or point to at least `n` valid elements.
 #[no_mangle]
-pub extern "C" fn ml_predict(
-    model_ptr: *const CModelBundle,
+pub unsafe extern "C" fn ml_multilinear_fit(
+    ptr: *mut CMultiLinearPredictor,
     features: *const PredictionFeatures,
-    result: *mut PredictionResult,
+    targets: *const f64,
+    n: usize,
 ) -> i32 {
-    if model_ptr.is_null() || features.is_null() || result.is_null() {
+    if ptr.is_null() || features.is_null() || targets.is_null() {
         return -1;
     }
 
     unsafe {
-        let model = &(*model_ptr).inner;
-        let features_ref = &*features;
-        let pred = predict(features_ref, model);
-        *result = pred;
-    }
+        let predictor = &mut (*ptr).inner;
+        let features_slice = std::slice::from_raw_parts(features, n);
+        let targets_slice = std::slice::from_raw_parts(targets, n);
 
-    0
+        match predictor.fit(features_slice, targets_slice) {
+            Ok(_) => 0,
+            Err(_) => -1,
+        }
+    }
 }
 
+/// # Safety
+///
+/// `ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types.
 #[no_mangle]
-pub extern "C" fn ml_save_model(model_ptr: *const CModelBundle, path: *const c_char) -> i32 {
-    if model_ptr.is_null() || path.is_null() {
-        return -1;
+pub unsafe extern "C" fn ml_multilinear_predict(
+    ptr: *const CMultiLinearPredictor,
+    features: *const PredictionFeatures,
+) -> f64 {
+    if ptr.is_null() || features.is_null() {
+        return 0.0;
     }
+    unsafe { (*ptr).inner.predict(&*features) }
+}
 
-    unsafe {
-        let model = &(*model_ptr).inner;
-        let path_str = CStr::from_ptr(path).to_str().unwrap_or("./ml_models/model.json");
-        
-        match save_model(model, path_str) {
-            Ok(_) => 0,
-            Err(_) => -1,
-        }
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CMultiLinearPredictor`.
+#[no_mangle]
+pub unsafe extern "C" fn multilinear_predictor_r2(ptr: *const CMultiLinearPredictor) -> f64 {
+    if ptr.is_null() {
+        return 0.0;
     }
+    unsafe { (*ptr).inner.r2_score() }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `multilinear_predictor_new`, and must
+/// not be used again after this call.
 #[no_mangle]
-pub extern "C" fn ml_model_free(ptr: *mut CModelBundle) {
+pub unsafe extern "C" fn multilinear_predictor_free(ptr: *mut CMultiLinearPredictor) {
     if !ptr.is_null() {
         unsafe {
             drop(Box::from_raw(ptr));
@@ -617,63 +2570,173 @@ pub extern "C" fn ml_model_free(ptr: *mut CModelBundle) {
     }
 }
 
-// ============================================================================
-// C FFI for Go integration (Legacy)
-// ============================================================================
-
 #[repr(C)]
-pub struct CLinearPredictor {
-    inner: LinearPredictor,
+pub struct CElasticNetPredictor {
+    inner: ElasticNetPredictor,
 }
 
+/// # Safety
+///
+/// Safe to call with any arguments; marked `unsafe` only because it is exposed via the C ABI.
 #[no_mangle]
-pub extern "C" fn linear_predictor_new() -> *mut CLinearPredictor {
-    Box::into_raw(Box::new(CLinearPredictor {
-        inner: LinearPredictor::new(),
+pub unsafe extern "C" fn elastic_net_predictor_new(alpha: f64, l1_ratio: f64) -> *mut CElasticNetPredictor {
+    Box::into_raw(Box::new(CElasticNetPredictor {
+        inner: ElasticNetPredictor::new(alpha, l1_ratio),
     }))
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CElasticNetPredictor`; `features` and `targets` must
+/// be null or point to at least `n` valid elements.
 #[no_mangle]
-pub extern "C" fn linear_predictor_fit(
-    ptr: *mut CLinearPredictor,
-    x: *const f64,
-    y: *const f64,
+pub unsafe extern "C" fn elastic_net_predictor_fit(
+    ptr: *mut CElasticNetPredictor,
+    features: *const PredictionFeatures,
+    targets: *const f64,
     n: usize,
 ) -> i32 {
-    if ptr.is_null() || x.is_null() || y.is_null() {
+    if ptr.is_null() || features.is_null() || targets.is_null() {
         return -1;
     }
 
     unsafe {
         let predictor = &mut (*ptr).inner;
-        let x_slice = std::slice::from_raw_parts(x, n);
-        let y_slice = std::slice::from_raw_parts(y, n);
+        let features_slice = std::slice::from_raw_parts(features, n);
+        let targets_slice = std::slice::from_raw_parts(targets, n);
 
-        match predictor.fit(x_slice, y_slice) {
+        match predictor.fit(features_slice, targets_slice) {
             Ok(_) => 0,
             Err(_) => -1,
         }
     }
 }
 
+/// # Safety
+///
+/// `ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types.
 #[no_mangle]
-pub extern "C" fn linear_predictor_predict(ptr: *const CLinearPredictor, x: f64) -> f64 {
-    if ptr.is_null() {
+pub unsafe extern "C" fn elastic_net_predictor_predict(
+    ptr: *const CElasticNetPredictor,
+    features: *const PredictionFeatures,
+) -> f64 {
+    if ptr.is_null() || features.is_null() {
         return 0.0;
     }
-    unsafe { (*ptr).inner.predict(x) }
+    unsafe { (*ptr).inner.predict(&*features) }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CElasticNetPredictor`.
 #[no_mangle]
-pub extern "C" fn linear_predictor_r2(ptr: *const CLinearPredictor) -> f64 {
+pub unsafe extern "C" fn elastic_net_predictor_r2(ptr: *const CElasticNetPredictor) -> f64 {
     if ptr.is_null() {
         return 0.0;
     }
     unsafe { (*ptr).inner.r2_score() }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `elastic_net_predictor_new`, and must
+/// not be used again after this call.
+#[no_mangle]
+pub unsafe extern "C" fn elastic_net_predictor_free(ptr: *mut CElasticNetPredictor) {
+    if !ptr.is_null() {
+        unsafe {
+            drop(Box::from_raw(ptr));
+        }
+    }
+}
+
+#[repr(C)]
+pub struct CGradientBoostedTrees {
+    inner: GradientBoostedTrees,
+}
+
+/// # Safety
+///
+/// Safe to call with any arguments; marked `unsafe` only because it is exposed via the C ABI.
+#[no_mangle]
+pub unsafe extern "C" fn gradient_boosted_trees_new(
+    n_trees: usize,
+    learning_rate: f64,
+    max_depth: usize,
+    subsample: f64,
+) -> *mut CGradientBoostedTrees {
+    Box::into_raw(Box::new(CGradientBoostedTrees {
+        inner: GradientBoostedTrees::new(n_trees, learning_rate, max_depth, subsample),
+    }))
+}
+
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CGradientBoostedTrees`; `features` and `targets` must
+/// be null or point to at least `n` valid elements.
+#[no_mangle]
+pub unsafe extern "C" fn gradient_boosted_trees_train(
+    ptr: *mut CGradientBoostedTrees,
+    features: *const PredictionFeatures,
+    targets: *const f64,
+    n: usize,
+) -> i32 {
+    if ptr.is_null() || features.is_null() || targets.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let predictor = &mut (*ptr).inner;
+        let features_slice = std::slice::from_raw_parts(features, n);
+        let targets_slice = std::slice::from_raw_parts(targets, n);
+        predictor.train(features_slice, targets_slice);
+    }
+    0
+}
+
+/// # Safety
+///
+/// `ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types.
+#[no_mangle]
+pub unsafe extern "C" fn gradient_boosted_trees_predict(
+    ptr: *const CGradientBoostedTrees,
+    features: *const PredictionFeatures,
+) -> f64 {
+    if ptr.is_null() || features.is_null() {
+        return 0.0;
+    }
+    unsafe { (*ptr).inner.predict(&*features) }
+}
+
+/// # Safety
+///
+/// `ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types; `targets` must be null or point to at least `n` valid `f64`s.
+#[no_mangle]
+pub unsafe extern "C" fn gradient_boosted_trees_r2(
+    ptr: *const CGradientBoostedTrees,
+    features: *const PredictionFeatures,
+    targets: *const f64,
+    n: usize,
+) -> f64 {
+    if ptr.is_null() || features.is_null() || targets.is_null() {
+        return 0.0;
+    }
+    unsafe {
+        let features_slice = std::slice::from_raw_parts(features, n);
+        let targets_slice = std::slice::from_raw_parts(targets, n);
+        (*ptr).inner.r2_score(features_slice, targets_slice)
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `gradient_boosted_trees_new`, and must
+/// not be used again after this call.
 #[no_mangle]
-pub extern "C" fn linear_predictor_free(ptr: *mut CLinearPredictor) {
+pub unsafe extern "C" fn gradient_boosted_trees_free(ptr: *mut CGradientBoostedTrees) {
     if !ptr.is_null() {
         unsafe {
             drop(Box::from_raw(ptr));
@@ -681,20 +2744,71 @@ pub extern "C" fn linear_predictor_free(ptr: *mut CLinearPredictor) {
     }
 }
 
+/// Train fresh `GradientBoostedTrees` on `features`/`vmaf_targets` and
+/// `psnr_targets` and substitute them into `model` for `vmaf_model`/
+/// `psnr_model`, the same drop-in swap as `ModelBundle::enable_boosted_trees`.
+/// Pass a null `vmaf_targets`/`psnr_targets` to leave that metric on the
+/// forest.
+/// # Safety
+///
+/// `model_ptr` and `features` must be null or point to live, validly-aligned values of their
+/// respective types; `vmaf_targets`/`psnr_targets` must each be null or point to at least `n`
+/// valid `f64`s.
+///
+#[no_mangle]
+pub unsafe extern "C" fn ml_model_enable_boosted_trees(
+    model_ptr: *mut CModelBundle,
+    features: *const PredictionFeatures,
+    vmaf_targets: *const f64,
+    psnr_targets: *const f64,
+    n: usize,
+    n_trees: usize,
+    learning_rate: f64,
+    max_depth: usize,
+    subsample: f64,
+) -> i32 {
+    if model_ptr.is_null() || features.is_null() {
+        return -1;
+    }
+
+    unsafe {
+        let model = &mut (*model_ptr).inner;
+        let features_slice = std::slice::from_raw_parts(features, n);
+        let vmaf_slice = (!vmaf_targets.is_null()).then(|| std::slice::from_raw_parts(vmaf_targets, n));
+        let psnr_slice = (!psnr_targets.is_null()).then(|| std::slice::from_raw_parts(psnr_targets, n));
+        model.enable_boosted_trees(
+            features_slice,
+            vmaf_slice,
+            psnr_slice,
+            n_trees,
+            learning_rate,
+            max_depth,
+            subsample,
+        );
+    }
+    0
+}
+
 #[repr(C)]
 pub struct CCostModel {
     inner: CostModel,
 }
 
+/// # Safety
+///
+/// Safe to call with any arguments; marked `unsafe` only because it is exposed via the C ABI.
 #[no_mangle]
-pub extern "C" fn cost_model_new(energy_cost_per_kwh: f64, cpu_cost_per_hour: f64) -> *mut CCostModel {
+pub unsafe extern "C" fn cost_model_new(energy_cost_per_kwh: f64, cpu_cost_per_hour: f64) -> *mut CCostModel {
     Box::into_raw(Box::new(CCostModel {
         inner: CostModel::new(energy_cost_per_kwh, cpu_cost_per_hour),
     }))
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CCostModel`.
 #[no_mangle]
-pub extern "C" fn cost_model_compute_total_cost(
+pub unsafe extern "C" fn cost_model_compute_total_cost(
     ptr: *const CCostModel,
     energy_joules: f64,
     duration_hours: f64,
@@ -705,8 +2819,12 @@ pub extern "C" fn cost_model_compute_total_cost(
     unsafe { (*ptr).inner.compute_total_cost(energy_joules, duration_hours) }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `cost_model_new`, and must not be used
+/// again after this call.
 #[no_mangle]
-pub extern "C" fn cost_model_free(ptr: *mut CCostModel) {
+pub unsafe extern "C" fn cost_model_free(ptr: *mut CCostModel) {
     if !ptr.is_null() {
         unsafe {
             drop(Box::from_raw(ptr));
@@ -719,8 +2837,11 @@ pub struct CRegionalPricing {
     inner: RegionalPricing,
 }
 
+/// # Safety
+///
+/// `region` must be null (the default region is used) or a valid, NUL-terminated C string.
 #[no_mangle]
-pub extern "C" fn regional_pricing_new(region: *const c_char) -> *mut CRegionalPricing {
+pub unsafe extern "C" fn regional_pricing_new(region: *const c_char) -> *mut CRegionalPricing {
     let region_str = if region.is_null() {
         "default"
     } else {
@@ -734,24 +2855,34 @@ pub extern "C" fn regional_pricing_new(region: *const c_char) -> *mut CRegionalP
     }))
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CRegionalPricing`.
 #[no_mangle]
-pub extern "C" fn regional_pricing_get_electricity_price(ptr: *const CRegionalPricing) -> f64 {
+pub unsafe extern "C" fn regional_pricing_get_electricity_price(ptr: *const CRegionalPricing) -> f64 {
     if ptr.is_null() {
         return 0.0;
     }
     unsafe { (*ptr).inner.electricity_price }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CRegionalPricing`.
 #[no_mangle]
-pub extern "C" fn regional_pricing_compute_co2(ptr: *const CRegionalPricing, energy_kwh: f64) -> f64 {
+pub unsafe extern "C" fn regional_pricing_compute_co2(ptr: *const CRegionalPricing, energy_kwh: f64) -> f64 {
     if ptr.is_null() {
         return 0.0;
     }
     unsafe { (*ptr).inner.compute_co2_emissions(energy_kwh) }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CRegionalPricing`; `to_currency` must be null or a
+/// valid, NUL-terminated C string.
 #[no_mangle]
-pub extern "C" fn regional_pricing_convert_currency(
+pub unsafe extern "C" fn regional_pricing_convert_currency(
     ptr: *const CRegionalPricing,
     amount: f64,
     to_currency: *const c_char,
@@ -765,8 +2896,12 @@ pub extern "C" fn regional_pricing_convert_currency(
     }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or point to a live `CRegionalPricing`; `buf` must be null or point to at
+/// least `buf_len` writable bytes.
 #[no_mangle]
-pub extern "C" fn regional_pricing_get_currency(
+pub unsafe extern "C" fn regional_pricing_get_currency(
     ptr: *const CRegionalPricing,
     buf: *mut c_char,
     buf_len: usize,
@@ -784,8 +2919,12 @@ pub extern "C" fn regional_pricing_get_currency(
     }
 }
 
+/// # Safety
+///
+/// `ptr` must be null or a value previously returned by `regional_pricing_new`, and must not be
+/// used again after this call.
 #[no_mangle]
-pub extern "C" fn regional_pricing_free(ptr: *mut CRegionalPricing) {
+pub unsafe extern "C" fn regional_pricing_free(ptr: *mut CRegionalPricing) {
     if !ptr.is_null() {
         unsafe {
             drop(Box::from_raw(ptr));
@@ -839,7 +2978,7 @@ mod tests {
         rf.train(&features, &targets);
         
         let pred = rf.predict(&features[0]);
-        assert!(pred >= 0.0 && pred <= 100.0);
+        assert!((0.0..=100.0).contains(&pred));
     }
 
     #[test]
@@ -1014,9 +3153,313 @@ mod tests {
     fn test_regional_pricing() {
         let pricing = RegionalPricing::new("us-east-1");
         assert_eq!(pricing.electricity_price, 0.13);
-        
+
         let co2 = pricing.compute_co2_emissions(10.0);
         assert!((co2 - 4.5).abs() < 0.01);
     }
+
+    #[test]
+    fn test_decision_tree_splits_two_clusters() {
+        // Two well-separated clusters: low-bitrate 720p samples target 10.0,
+        // high-bitrate 4K samples target 90.0. Bitrate, resolution, frame
+        // rate and motion each separate them, so a single split (on
+        // whichever of those the shuffled candidate order reaches first)
+        // should route every sample to the right leaf.
+        let features = vec![
+            PredictionFeatures { bitrate_kbps: 500.0, resolution_width: 1280, resolution_height: 720, frame_rate: 30.0, frame_drop: 0.0, motion_intensity: 0.3 },
+            PredictionFeatures { bitrate_kbps: 600.0, resolution_width: 1280, resolution_height: 720, frame_rate: 30.0, frame_drop: 0.0, motion_intensity: 0.3 },
+            PredictionFeatures { bitrate_kbps: 700.0, resolution_width: 1280, resolution_height: 720, frame_rate: 30.0, frame_drop: 0.0, motion_intensity: 0.3 },
+            PredictionFeatures { bitrate_kbps: 8000.0, resolution_width: 3840, resolution_height: 2160, frame_rate: 60.0, frame_drop: 0.0, motion_intensity: 0.8 },
+            PredictionFeatures { bitrate_kbps: 8100.0, resolution_width: 3840, resolution_height: 2160, frame_rate: 60.0, frame_drop: 0.0, motion_intensity: 0.8 },
+            PredictionFeatures { bitrate_kbps: 8200.0, resolution_width: 3840, resolution_height: 2160, frame_rate: 60.0, frame_drop: 0.0, motion_intensity: 0.8 },
+        ];
+        let targets = vec![10.0, 10.0, 10.0, 90.0, 90.0, 90.0];
+        let indices: Vec<usize> = (0..features.len()).collect();
+        let mut rng = rand::thread_rng();
+
+        let tree = DecisionTree::fit(&features, &targets, &indices, 6, 1, 1.0, &mut rng);
+
+        for (feat, &target) in features.iter().zip(targets.iter()) {
+            assert!((tree.predict(feat) - target).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_elastic_net_zeroes_irrelevant_column() {
+        // Target is an exact linear function of bitrate_kbps alone; the
+        // other five columns vary independently of it. Pure lasso
+        // (l1_ratio = 1.0) should drive their coefficients to zero.
+        let features = vec![
+            PredictionFeatures { bitrate_kbps: 1000.0, resolution_width: 1280, resolution_height: 720, frame_rate: 24.0, frame_drop: 0.02, motion_intensity: 0.9 },
+            PredictionFeatures { bitrate_kbps: 2000.0, resolution_width: 640, resolution_height: 360, frame_rate: 60.0, frame_drop: 0.0, motion_intensity: 0.1 },
+            PredictionFeatures { bitrate_kbps: 3000.0, resolution_width: 1920, resolution_height: 1080, frame_rate: 30.0, frame_drop: 0.05, motion_intensity: 0.5 },
+            PredictionFeatures { bitrate_kbps: 4000.0, resolution_width: 3840, resolution_height: 2160, frame_rate: 25.0, frame_drop: 0.01, motion_intensity: 0.7 },
+            PredictionFeatures { bitrate_kbps: 5000.0, resolution_width: 854, resolution_height: 480, frame_rate: 50.0, frame_drop: 0.03, motion_intensity: 0.2 },
+        ];
+        let targets: Vec<f64> = features.iter().map(|f| f.bitrate_kbps as f64 * 0.02).collect();
+
+        let mut predictor = ElasticNetPredictor::new(5.0, 1.0);
+        predictor.fit(&features, &targets).unwrap();
+
+        assert!(predictor.coefficients[0].abs() > 1e-6, "bitrate coefficient should stay nonzero");
+        for &coef in &predictor.coefficients[1..] {
+            assert_eq!(coef, 0.0, "irrelevant column should be zeroed by the L1 penalty");
+        }
+    }
+
+    #[test]
+    fn test_model_binary_round_trip() {
+        let model = create_default_model();
+        let path = "/tmp/test_model_round_trip.bin";
+
+        save_model_binary(&model, path).expect("Failed to save binary model");
+        let loaded = load_model_binary(path).expect("Failed to load binary model");
+
+        assert_eq!(loaded.version, model.version);
+
+        let features = PredictionFeatures {
+            bitrate_kbps: 2500.0,
+            resolution_width: 1920,
+            resolution_height: 1080,
+            frame_rate: 30.0,
+            frame_drop: 0.01,
+            motion_intensity: 0.5,
+        };
+        assert_eq!(loaded.vmaf_model.predict(&features), model.vmaf_model.predict(&features));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_cross_validate_fold_partitioning() {
+        let mut rf = SimpleRandomForest::new(5);
+        let features: Vec<PredictionFeatures> = (0..10)
+            .map(|i| PredictionFeatures {
+                bitrate_kbps: 500.0 + i as f32 * 500.0,
+                resolution_width: 1280,
+                resolution_height: 720,
+                frame_rate: 30.0,
+                frame_drop: 0.01,
+                motion_intensity: 0.5,
+            })
+            .collect();
+        let targets: Vec<f64> = (0..10).map(|i| 10.0 + i as f64 * 5.0).collect();
+
+        // Training the forest up front is unnecessary for cross_validate (it
+        // clones and retrains per fold), but confirms an already-trained
+        // forest is a valid starting point.
+        rf.train(&features, &targets.iter().map(|&t| t as f32).collect::<Vec<_>>());
+
+        let report = cross_validate(&rf, &features, &targets, 5);
+
+        // 5 folds over 10 samples: every fold gets exactly 2 held-out points.
+        assert_eq!(report.fold_r2.len(), 5);
+        assert!(report.mean_rmse >= 0.0);
+
+        // A single observation can't be split into train/test folds.
+        let one_feature = vec![features[0].clone()];
+        let one_target = vec![targets[0]];
+        let report = cross_validate(&rf, &one_feature, &one_target, 5);
+        assert!(report.fold_r2.is_empty());
+        assert_eq!(report.mean_r2, 0.0);
+    }
+
+    #[test]
+    fn test_random_forest_defaults_generalize_on_held_out_folds() {
+        // `min_leaf_size: 1` and `MIN_BAGGING_SAMPLES` were tuned against the
+        // 3-point self-scoring accuracy tests above; this instead checks the
+        // same defaults against folds the forest never trained on, over a
+        // sample large enough (30, above MIN_BAGGING_SAMPLES) for bootstrap
+        // bagging to actually kick in, so the numbers are defensible beyond
+        // "it memorized its own training set".
+        let features: Vec<PredictionFeatures> = (0..30)
+            .map(|i| PredictionFeatures {
+                bitrate_kbps: 500.0 + i as f32 * 200.0,
+                resolution_width: 1920,
+                resolution_height: 1080,
+                frame_rate: 30.0,
+                frame_drop: 0.01,
+                motion_intensity: 0.5,
+            })
+            .collect();
+        // A smooth, monotonic bitrate -> VMAF curve, the kind of relationship
+        // this model exists to approximate.
+        let targets: Vec<f64> = (0..30)
+            .map(|i| (60.0 + 8.0 * ((1.0 + i as f64 * 0.2).sqrt())).min(98.0))
+            .collect();
+
+        let rf = SimpleRandomForest::new(20);
+        let report = cross_validate(&rf, &features, &targets, 5);
+
+        assert_eq!(report.fold_r2.len(), 5);
+        assert!(
+            report.mean_r2 >= 0.7,
+            "held-out mean R² {} is too low for min_leaf_size=1 defaults to be considered well-generalizing",
+            report.mean_r2
+        );
+    }
+
+    #[test]
+    fn test_loss_least_absolute_deviation_ignores_outlier() {
+        // A single large outlier should barely move an LAD leaf's value
+        // (the median), while it would drag a SquaredError leaf (the mean)
+        // toward it.
+        let indices = vec![0, 1, 2, 3];
+        let residuals = vec![1.0, 2.0, 3.0, 1000.0];
+
+        let lad_value = Loss::LeastAbsoluteDeviation.leaf_value(&indices, &residuals);
+        let squared_value = Loss::SquaredError.leaf_value(&indices, &residuals);
+
+        assert!((lad_value - 2.5).abs() < 0.01);
+        assert!(squared_value > 200.0);
+        assert!(lad_value < squared_value);
+    }
+
+    #[test]
+    fn test_loss_gradients() {
+        assert_eq!(Loss::SquaredError.gradient(10.0, 4.0), 6.0);
+        assert_eq!(Loss::LeastAbsoluteDeviation.gradient(10.0, 4.0), 1.0);
+        assert_eq!(Loss::LeastAbsoluteDeviation.gradient(4.0, 10.0), -1.0);
+
+        // Huber is linear (clipped to ±delta) beyond delta, exact within it.
+        let huber = Loss::Huber { delta: 2.0 };
+        assert_eq!(huber.gradient(10.0, 9.0), 1.0);
+        assert_eq!(huber.gradient(10.0, 0.0), 2.0);
+        assert_eq!(huber.gradient(0.0, 10.0), -2.0);
+    }
+
+    #[test]
+    fn test_multi_linear_predictor_fits_linear_relationship() {
+        let features: Vec<PredictionFeatures> = (0..20)
+            .map(|i| PredictionFeatures {
+                bitrate_kbps: 500.0 + i as f32 * 250.0,
+                resolution_width: 1920,
+                resolution_height: 1080,
+                frame_rate: 30.0,
+                frame_drop: 0.0,
+                motion_intensity: 0.5,
+            })
+            .collect();
+        // VMAF as a simple linear function of bitrate alone.
+        let targets: Vec<f64> = features.iter().map(|f| 40.0 + f.bitrate_kbps as f64 * 0.02).collect();
+
+        let mut predictor = MultiLinearPredictor::new(0.01);
+        predictor.fit(&features, &targets).unwrap();
+
+        assert!(predictor.r2_score() > 0.99);
+
+        let held_out = PredictionFeatures {
+            bitrate_kbps: 6000.0,
+            ..features[0].clone()
+        };
+        let expected = 40.0 + 6000.0 * 0.02;
+        assert!((predictor.predict(&held_out) - expected).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_gradient_boosted_trees_standalone_fits_training_data() {
+        let features: Vec<PredictionFeatures> = (0..20)
+            .map(|i| PredictionFeatures {
+                bitrate_kbps: 500.0 + i as f32 * 250.0,
+                resolution_width: 1920,
+                resolution_height: 1080,
+                frame_rate: 30.0,
+                frame_drop: 0.0,
+                motion_intensity: 0.5,
+            })
+            .collect();
+        let targets: Vec<f64> = (0..20).map(|i| 50.0 + i as f64 * 2.0).collect();
+
+        let mut trees = GradientBoostedTrees::new(30, 0.1, 3, 1.0);
+        trees.train(&features, &targets);
+
+        let r2 = trees.r2_score(&features, &targets);
+        assert!(r2 > 0.9, "GradientBoostedTrees train R² {} too low", r2);
+
+        // predict() must stay within the model's [0, 100] output range.
+        for feat in &features {
+            let pred = trees.predict(feat);
+            assert!((0.0..=100.0).contains(&pred));
+        }
+    }
+
+    #[test]
+    fn test_recommend_bitrate_constrained_rejects_rung_over_cost_ceiling() {
+        let model = create_default_model();
+        let features = PredictionFeatures {
+            bitrate_kbps: 2500.0,
+            resolution_width: 1920,
+            resolution_height: 1080,
+            frame_rate: 30.0,
+            frame_drop: 0.01,
+            motion_intensity: 0.5,
+        };
+
+        // An unreachable cost ceiling of 0.0 admits no candidate.
+        let result = recommend_bitrate_constrained(&features, &model, 0.0, 1000.0, 0.0);
+        assert!(result.chosen.is_none());
+        assert!(!result.pareto_front.is_empty());
+
+        // A generous ceiling should admit at least one candidate, and every
+        // swept rung in the front must itself respect the sweep bounds.
+        let result = recommend_bitrate_constrained(&features, &model, 1000.0, 1000.0, 0.0);
+        assert!(result.chosen.is_some());
+        for candidate in &result.pareto_front {
+            assert!(candidate.bitrate_kbps >= 200 && candidate.bitrate_kbps <= 20_000);
+        }
+    }
+
+    #[test]
+    fn test_simulate_picks_cheapest_rung_above_quality_floor() {
+        let model = create_default_model();
+        let features = PredictionFeatures {
+            bitrate_kbps: 2500.0,
+            resolution_width: 1920,
+            resolution_height: 1080,
+            frame_rate: 30.0,
+            frame_drop: 0.01,
+            motion_intensity: 0.5,
+        };
+        let cost_model = CostModel::new(0.12, 0.50);
+        let pricing = RegionalPricing::new("us-east-1");
+
+        let simulation = simulate(&features, &model, 1.0, &cost_model, &pricing, 0.0);
+        let chosen = simulation.chosen.expect("a min_vmaf of 0.0 should always admit a rung");
+
+        for rung in &simulation.ladder {
+            if rung.predicted_vmaf >= 0.0 {
+                assert!(chosen.session_cost_usd <= rung.session_cost_usd + 1e-9);
+            }
+        }
+
+        // An unreachable quality floor admits nothing.
+        let simulation = simulate(&features, &model, 1.0, &cost_model, &pricing, 1000.0);
+        assert!(simulation.chosen.is_none());
+    }
+
+    #[test]
+    fn test_permutation_importance_ranks_relied_upon_feature_highest() {
+        let features: Vec<PredictionFeatures> = (0..20)
+            .map(|i| PredictionFeatures {
+                bitrate_kbps: 500.0 + i as f32 * 250.0,
+                resolution_width: 1920,
+                resolution_height: 1080,
+                frame_rate: 30.0,
+                frame_drop: 0.0,
+                motion_intensity: 0.5,
+            })
+            .collect();
+        // Target depends only on bitrate; every other feature is constant
+        // (and therefore can't possibly matter to a fitted model).
+        let targets: Vec<f64> = features.iter().map(|f| f.bitrate_kbps as f64 * 0.02).collect();
+
+        let mut rf = SimpleRandomForest::new(20);
+        rf.train(&features, &targets.iter().map(|&t| t as f32).collect::<Vec<_>>());
+
+        let importances = permutation_importance(&rf, &features, &targets);
+        assert_eq!(importances.len(), N_FEATURES);
+        assert_eq!(importances[0].feature, "bitrate_kbps");
+        assert!(importances[0].importance > importances.last().unwrap().importance);
+    }
 }
 
@@ -0,0 +1,198 @@
+//! Per-frame VMAF distribution analysis.
+//!
+//! A single scalar VMAF prediction hides how much an encode's quality
+//! actually swings frame to frame; the 1st-percentile "worst moments" drive
+//! perceived quality far more than the mean does. This module ingests a
+//! per-frame VMAF score vector (e.g. parsed from an ffmpeg/libvmaf JSON log's
+//! `frames[].metrics.vmaf`), summarizes it, and can render the scores as an
+//! SVG quality-over-time chart.
+
+use plotters::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Summary statistics over a per-frame VMAF score vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmafDistributionSummary {
+    pub mean: f64,
+    /// Harmonic mean: penalizes low-VMAF outlier frames far more than the
+    /// arithmetic mean, closer to how viewers perceive sustained dips.
+    pub harmonic_mean: f64,
+    pub p1: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+}
+
+impl VmafDistributionSummary {
+    /// Compute every statistic from a per-frame VMAF score vector. Returns
+    /// `None` for an empty vector, since there's nothing to summarize.
+    pub fn from_frame_scores(scores: &[f64]) -> Option<Self> {
+        if scores.is_empty() {
+            return None;
+        }
+
+        let mut sorted = scores.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+        let harmonic_mean =
+            sorted.len() as f64 / sorted.iter().map(|&s| 1.0 / s.max(1e-9)).sum::<f64>();
+
+        Some(Self {
+            mean,
+            harmonic_mean,
+            p1: percentile_of_sorted(&sorted, 1.0),
+            p25: percentile_of_sorted(&sorted, 25.0),
+            p50: percentile_of_sorted(&sorted, 50.0),
+            p75: percentile_of_sorted(&sorted, 75.0),
+        })
+    }
+}
+
+/// Linearly-interpolated `percentile` (0-100) of an already-sorted slice.
+fn percentile_of_sorted(sorted: &[f64], percentile: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+
+    let rank = (percentile / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+
+    if lower == upper {
+        sorted[lower]
+    } else {
+        let weight = rank - lower as f64;
+        sorted[lower] * (1.0 - weight) + sorted[upper] * weight
+    }
+}
+
+/// Render a quality-over-time line chart to `output_path` with horizontal
+/// reference lines at `summary`'s percentiles, so sustained quality dips are
+/// visible at a glance instead of buried in a mean.
+pub fn plot_vmaf_over_time(
+    scores: &[f64],
+    summary: &VmafDistributionSummary,
+    output_path: &str,
+) -> Result<(), String> {
+    let root = SVGBackend::new(output_path, (1200, 600)).into_drawing_area();
+    root.fill(&WHITE)
+        .map_err(|e| format!("Failed to initialize SVG canvas: {}", e))?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Per-Frame VMAF", ("sans-serif", 24))
+        .margin(20)
+        .x_label_area_size(30)
+        .y_label_area_size(40)
+        .build_cartesian_2d(0f64..scores.len().max(1) as f64, 0f64..100f64)
+        .map_err(|e| format!("Failed to build chart: {}", e))?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Frame")
+        .y_desc("VMAF")
+        .draw()
+        .map_err(|e| format!("Failed to draw mesh: {}", e))?;
+
+    chart
+        .draw_series(LineSeries::new(
+            scores.iter().enumerate().map(|(i, &s)| (i as f64, s)),
+            &BLUE,
+        ))
+        .map_err(|e| format!("Failed to draw VMAF series: {}", e))?
+        .label("VMAF")
+        .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], BLUE));
+
+    let reference_lines: [(&str, f64, RGBColor); 4] = [
+        ("p1", summary.p1, RED),
+        ("p25", summary.p25, MAGENTA),
+        ("p50", summary.p50, GREEN),
+        ("p75", summary.p75, CYAN),
+    ];
+
+    for (label, value, color) in reference_lines {
+        chart
+            .draw_series(LineSeries::new(
+                [(0f64, value), (scores.len() as f64, value)],
+                &color,
+            ))
+            .map_err(|e| format!("Failed to draw {} reference line: {}", label, e))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| format!("Failed to draw legend: {}", e))?;
+
+    root.present().map_err(|e| format!("Failed to write SVG: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_frame_scores_empty_returns_none() {
+        assert!(VmafDistributionSummary::from_frame_scores(&[]).is_none());
+    }
+
+    #[test]
+    fn test_from_frame_scores_single_value() {
+        let summary = VmafDistributionSummary::from_frame_scores(&[80.0]).unwrap();
+        assert_eq!(summary.mean, 80.0);
+        assert_eq!(summary.harmonic_mean, 80.0);
+        assert_eq!(summary.p1, 80.0);
+        assert_eq!(summary.p50, 80.0);
+    }
+
+    #[test]
+    fn test_harmonic_mean_near_zero_score_does_not_blow_up() {
+        // A single near-zero frame would send a naive harmonic mean to
+        // infinity; `from_frame_scores` floors each reciprocal's denominator
+        // at `1e-9` to keep it finite.
+        let summary = VmafDistributionSummary::from_frame_scores(&[0.0, 90.0, 92.0]).unwrap();
+        assert!(summary.harmonic_mean.is_finite());
+        assert!(summary.harmonic_mean >= 0.0);
+    }
+
+    #[test]
+    fn test_harmonic_mean_penalizes_outlier_more_than_arithmetic_mean() {
+        let summary = VmafDistributionSummary::from_frame_scores(&[10.0, 90.0, 90.0, 90.0]).unwrap();
+        assert!(summary.harmonic_mean < summary.mean);
+    }
+
+    #[test]
+    fn test_percentile_of_sorted_boundaries() {
+        let sorted = vec![10.0, 20.0, 30.0, 40.0, 50.0];
+        assert_eq!(percentile_of_sorted(&sorted, 50.0), 30.0);
+        assert_eq!(percentile_of_sorted(&sorted, 1.0), 10.4);
+        assert_eq!(percentile_of_sorted(&sorted, 25.0), 20.0);
+        assert_eq!(percentile_of_sorted(&sorted, 75.0), 40.0);
+    }
+
+    #[test]
+    fn test_percentile_of_sorted_single_element() {
+        assert_eq!(percentile_of_sorted(&[42.0], 1.0), 42.0);
+        assert_eq!(percentile_of_sorted(&[42.0], 99.0), 42.0);
+    }
+
+    #[test]
+    fn test_plot_vmaf_over_time_produces_non_empty_svg() {
+        let scores = vec![70.0, 80.0, 90.0, 85.0, 95.0];
+        let summary = VmafDistributionSummary::from_frame_scores(&scores).unwrap();
+        let path = std::env::temp_dir().join("vmaf_distribution_test_plot.svg");
+        let path_str = path.to_str().unwrap();
+
+        plot_vmaf_over_time(&scores, &summary, path_str).expect("rendering should succeed");
+
+        let contents = std::fs::read_to_string(&path).expect("SVG file should have been written");
+        assert!(!contents.is_empty());
+        assert!(contents.contains("<svg"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}